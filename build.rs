@@ -6,7 +6,17 @@ fn main() {
     let out_dir = std::env::var("OUT_DIR").unwrap();
     let path = std::path::Path::new(&out_dir).join("gl.rs");
     let mut file = std::fs::File::create(path).unwrap();
-    Registry::new(Api::Gl, (3, 2), Profile::Core, Fallbacks::All, [])
+    let extensions = [
+        "GL_ARB_base_instance",
+        "GL_ARB_draw_buffers_blend",
+        "GL_ARB_draw_indirect",
+        "GL_ARB_instanced_arrays",
+        "GL_ARB_multi_draw_indirect",
+        "GL_ARB_vertex_attrib_64bit",
+        "GL_ARB_vertex_type_10f_11f_11f_rev",
+        "GL_ARB_vertex_type_2_10_10_10_rev",
+    ];
+    Registry::new(Api::Gl, (3, 2), Profile::Core, Fallbacks::All, extensions)
         .write_bindings(StructGenerator, &mut file)
         .unwrap();
 }
@@ -0,0 +1,305 @@
+//! `#[derive(Vertex)]` for the `gpu` crate.
+//!
+//! Computes the format, offset, and stride of each field of a `#[repr(C)]`
+//! struct and implements `gpu::vertex_array::Vertex`, which builds the
+//! attribute array for a given `Buffer`.
+//!
+//! Each field becomes the attribute at the index following the previous
+//! field, starting from zero. Fields may be annotated with:
+//!
+//! * `#[vertex(location = N)]` - binds the field to attribute index `N`.
+//! * `#[vertex(format = "U8Norm")]` - overrides the component type, e.g.
+//!   to read a `[u8; 4]` as normalized rationals rather than integers. The
+//!   override must have the same component width and signedness as the
+//!   field type, with `"F16"` read from `u16`. The component count is
+//!   always taken from the field type, except for the packed formats such
+//!   as `"I2_10_10_10RevNorm"`, which require a `u32` field.
+//! * `#[vertex(skip)]` - the field is not bound to any attribute.
+
+extern crate proc_macro;
+extern crate syn;
+#[macro_use] extern crate quote;
+
+use proc_macro::TokenStream;
+
+/// Component types supported by `gpu::buffer::Format`.
+const FORMATS: &'static [&'static str] = &[
+    "F16",
+    "F32",
+    "F64",
+    "I8",
+    "I8Norm",
+    "I8Scaled",
+    "I16",
+    "I16Norm",
+    "I16Scaled",
+    "I32",
+    "I32Scaled",
+    "U8",
+    "U8Norm",
+    "U8Scaled",
+    "U16",
+    "U16Norm",
+    "U16Scaled",
+    "U32",
+    "U32Scaled",
+];
+
+/// Packed formats supported by `gpu::buffer::Format`, whose component
+/// count is implied.
+const PACKED_FORMATS: &'static [&'static str] = &[
+    "I2_10_10_10Rev",
+    "I2_10_10_10RevNorm",
+    "U2_10_10_10Rev",
+    "U2_10_10_10RevNorm",
+    "U10F11F11FRev",
+];
+
+/// Parsed `#[vertex(...)]` field options.
+#[derive(Default)]
+struct Options {
+    location: Option<u64>,
+    format: Option<String>,
+    skip: bool,
+}
+
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let source = input.to_string();
+    let ast = syn::parse_derive_input(&source).expect("failed to parse input");
+    impl_vertex(&ast).parse().expect("failed to parse output")
+}
+
+fn impl_vertex(ast: &syn::DeriveInput) -> quote::Tokens {
+    let name = &ast.ident;
+    if !is_repr_c(&ast.attrs) {
+        panic!("#[derive(Vertex)] requires #[repr(C)] on `{}`", name);
+    }
+    let fields = match ast.body {
+        syn::Body::Struct(syn::VariantData::Struct(ref fields)) => fields,
+        _ => panic!("#[derive(Vertex)] only supports structs with named fields"),
+    };
+
+    let mut next_location = 0;
+    let mut used = Vec::new();
+    let mut bindings = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let options = parse_options(&field.attrs);
+        if options.skip {
+            continue;
+        }
+        let location = options.location.unwrap_or(next_location);
+        if used.contains(&location) {
+            panic!("attribute location {} bound more than once", location);
+        }
+        used.push(location);
+        next_location = location + 1;
+
+        let (component, count) = component_type(&field.ty, ident);
+        let variant = options.format.unwrap_or(component.clone());
+        let format = if FORMATS.contains(&variant.as_str()) {
+            if !is_compatible(&variant, &component) {
+                panic!(
+                    "vertex format `{}` does not match the `{}` components of `{}`",
+                    variant,
+                    component.to_lowercase(),
+                    ident,
+                );
+            }
+            let variant = quote::Ident::new(variant);
+            let count = count as u8;
+            quote! { ::gpu::buffer::Format::#variant(#count) }
+        } else if PACKED_FORMATS.contains(&variant.as_str()) {
+            if component != "U32" || count != 1 {
+                panic!("packed vertex format `{}` requires `{}` to be a `u32`", variant, ident);
+            }
+            let variant = quote::Ident::new(variant);
+            quote! { ::gpu::buffer::Format::#variant }
+        } else {
+            panic!("unknown vertex format `{}` for `{}`", variant, ident);
+        };
+        let location = location as usize;
+        bindings.push(quote! {
+            attributes[#location] = Some(
+                ::gpu::vertex_array::Attribute::Buffer(
+                    ::gpu::buffer::Accessor::new(
+                        buffer.clone(),
+                        #format,
+                        {
+                            let vertex: #name = unsafe { ::std::mem::zeroed() };
+                            let base = &vertex as *const #name as usize;
+                            let field = &vertex.#ident as *const _ as usize;
+                            field - base
+                        },
+                        ::std::mem::size_of::<#name>(),
+                    ),
+                ),
+            );
+        });
+    }
+
+    let len = used.iter().max().map(|&max| max as usize + 1).unwrap_or(0);
+    quote! {
+        impl ::gpu::vertex_array::Vertex for #name {
+            fn attributes(
+                buffer: &::gpu::buffer::Buffer,
+            ) -> Vec<Option<::gpu::vertex_array::Attribute>> {
+                let mut attributes = vec![None; #len];
+                #(#bindings)*
+                attributes
+            }
+        }
+    }
+}
+
+/// Returns `true` if a format variant reads components of the same size
+/// as the field component variant, e.g. `"U8Norm"` from `"U8"`.
+fn is_compatible(format: &str, component: &str) -> bool {
+    format.starts_with(component) || (format == "F16" && component == "U16")
+}
+
+/// Returns `true` if the attributes contain `#[repr(C)]`.
+fn is_repr_c(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| match attr.value {
+        syn::MetaItem::List(ref ident, ref items) if ident == "repr" => {
+            items.iter().any(|item| match *item {
+                syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref word)) => {
+                    word == "C"
+                }
+                _ => false,
+            })
+        }
+        _ => false,
+    })
+}
+
+/// Parses the `#[vertex(...)]` attributes of a field.
+fn parse_options(attrs: &[syn::Attribute]) -> Options {
+    let mut options = Options::default();
+    for attr in attrs {
+        let items = match attr.value {
+            syn::MetaItem::List(ref ident, ref items) if ident == "vertex" => items,
+            _ => continue,
+        };
+        for item in items {
+            match *item {
+                syn::NestedMetaItem::MetaItem(
+                    syn::MetaItem::NameValue(ref key, syn::Lit::Int(value, _)),
+                ) if key == "location" => {
+                    options.location = Some(value);
+                }
+                syn::NestedMetaItem::MetaItem(
+                    syn::MetaItem::NameValue(ref key, syn::Lit::Str(ref value, _)),
+                ) if key == "format" => {
+                    options.format = Some(value.clone());
+                }
+                syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref key))
+                    if key == "skip" =>
+                {
+                    options.skip = true;
+                }
+                _ => panic!("unrecognized #[vertex] option"),
+            }
+        }
+    }
+    options
+}
+
+/// Returns the format variant and component count for a field type,
+/// e.g. `("F32", 3)` for `[f32; 3]`.
+fn component_type(ty: &syn::Ty, field: &syn::Ident) -> (String, u64) {
+    let (scalar, count) = match *ty {
+        syn::Ty::Array(ref ty, syn::ConstExpr::Lit(syn::Lit::Int(n, _))) => (&**ty, n),
+        _ => (ty, 1),
+    };
+    if count < 1 || count > 4 {
+        panic!("`{}` must have between 1 and 4 components", field);
+    }
+    let name = match *scalar {
+        syn::Ty::Path(None, ref path) if path.segments.len() == 1 => {
+            path.segments[0].ident.as_ref().to_string()
+        }
+        _ => panic!("unsupported vertex attribute type for `{}`", field),
+    };
+    let variant = match name.as_str() {
+        "f32" => "F32",
+        "f64" => "F64",
+        "i8" => "I8",
+        "i16" => "I16",
+        "i32" => "I32",
+        "u8" => "U8",
+        "u16" => "U16",
+        "u32" => "U32",
+        _ => panic!("unsupported vertex component type `{}` for `{}`", name, field),
+    };
+    (variant.to_string(), count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(source: &str) -> String {
+        let ast = syn::parse_derive_input(source).unwrap();
+        impl_vertex(&ast).to_string()
+    }
+
+    #[test]
+    fn expands_formats() {
+        let output = expand("
+            #[repr(C)]
+            struct Vertex {
+                position: [f32; 3],
+                #[vertex(format = \"U8Norm\")]
+                color: [u8; 4],
+                #[vertex(location = 4, format = \"F16\")]
+                uv: [u16; 2],
+                #[vertex(skip)]
+                padding: u32,
+            }
+        ");
+        assert!(output.contains("vec ! [ None ; 5usize ]"));
+        assert!(output.contains("Format :: F32 ( 3u8 )"));
+        assert!(output.contains("Format :: U8Norm ( 4u8 )"));
+        assert!(output.contains("Format :: F16 ( 2u8 )"));
+        assert!(output.contains("attributes [ 4usize ]"));
+        assert!(!output.contains("padding"));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the `u8` components of `color`")]
+    fn rejects_wider_format() {
+        expand("
+            #[repr(C)]
+            struct Vertex {
+                #[vertex(format = \"F32\")]
+                color: [u8; 4],
+            }
+        ");
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the `i16` components of `normal`")]
+    fn rejects_other_signedness() {
+        expand("
+            #[repr(C)]
+            struct Vertex {
+                #[vertex(format = \"U16Norm\")]
+                normal: [i16; 3],
+            }
+        ");
+    }
+
+    #[test]
+    #[should_panic(expected = "requires `packed` to be a `u32`")]
+    fn rejects_packed_non_u32() {
+        expand("
+            #[repr(C)]
+            struct Vertex {
+                #[vertex(format = \"I2_10_10_10RevNorm\")]
+                packed: [i32; 1],
+            }
+        ");
+    }
+}
@@ -18,6 +18,7 @@ use glutin::WindowEvent;
 
 /// Size: 24
 #[repr(C)]
+#[derive(Clone, Copy)]
 struct Vertex {
     /// Offset: 0
     position: [f32; 3],
@@ -25,6 +26,8 @@ struct Vertex {
     normal: [f32; 3],
 }
 
+unsafe impl buf::Pod for Vertex {}
+
 #[derive(Clone)]
 struct Window(sync::Arc<glutin::GlWindow>);
 
@@ -85,14 +88,14 @@ fn main() {
     let window = Window(sync::Arc::new(window));
     let (_default_framebuffer, factory) = gpu::init(window.clone());
 
-    let mut vbuf = factory.empty_buffer(buf::Kind::Array, buf::Usage::StaticDraw);
-    factory.initialize_buffer(&mut vbuf, TRIANGLE_DATA);
+    let mut vbuf = factory.empty_typed_buffer(buf::Kind::Array, buf::Usage::StaticDraw);
+    factory.initialize_typed_buffer(&mut vbuf, TRIANGLE_DATA);
 
-    let positions = buf::Accessor::new(vbuf.clone(), POSITION, 0, 24);
-    let normals = buf::Accessor::new(vbuf, NORMAL, 12, 24);
-    let attributes = [Some(positions), Some(normals), None, None, None, None, None, None];
+    let positions = vbuf.accessor(POSITION, 0);
+    let normals = vbuf.accessor(NORMAL, 12);
+    let attributes = [Some(positions.into()), Some(normals.into())];
     let indices = None;
-    let vertex_array = factory.vertex_array(attributes, indices);
+    let vertex_array = factory.vertex_array(&attributes, indices);
 
     let vertex_shader = {
         let mut source = util::read_file_to_end("examples/deferred/gbuffer.vert").unwrap();
@@ -119,8 +122,8 @@ fn main() {
     };
     let invocation = gpu::program::Invocation {
         program: &program,
-        uniforms: [None; gpu::program::MAX_UNIFORM_BLOCKS],
-        samplers: [None; gpu::program::MAX_SAMPLERS],
+        uniforms: &[],
+        samplers: &[],
     };
     let (width, height) = (1920, 1080);
     let format = tex::format::F32::Rgb;
@@ -130,7 +133,6 @@ fn main() {
     let color_attachments = [
         gpu::framebuffer::ColorAttachment::Texture2(position_target.clone()),
         gpu::framebuffer::ColorAttachment::Texture2(normal_target.clone()),
-        gpu::framebuffer::ColorAttachment::None,
     ];
     let format = tex::format::F32::Depth;
     let depth_target = factory.texture2(width, height, false, format);
@@ -139,7 +141,7 @@ fn main() {
     let framebuffer = factory.framebuffer(
         width,
         height,
-        color_attachments,
+        &color_attachments,
         depth_stencil_attachment,
     );
 
@@ -12,18 +12,29 @@ use glutin::Event;
 use glutin::GlContext;
 use glutin::VirtualKeyCode as Key;
 use glutin::WindowEvent;
-use gpu::program::{Bindings, SamplerBinding, UniformBlockBinding};
+use gpu::program::{
+    Bindings,
+    SamplerBinding,
+    TransformFeedbackVaryings,
+    UniformBlockBinding,
+};
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 struct Vertex {
     position: [f32; 3],
 }
 
+unsafe impl buf::Pod for Vertex {}
+
 #[repr(C)]
+#[derive(Clone, Copy)]
 struct UniformBlock {
     color: [f32; 4],
 }
 
+unsafe impl buf::Pod for UniformBlock {}
+
 #[derive(Clone)]
 struct Window(sync::Arc<glutin::GlWindow>);
 
@@ -46,18 +57,11 @@ impl ops::Deref for Window {
 }
 
 const BINDINGS: Bindings = Bindings {
-    uniform_blocks: [
-        UniformBlockBinding::Required(b"b_Locals\0"),
-        UniformBlockBinding::None,
-        UniformBlockBinding::None,
-        UniformBlockBinding::None,
-    ],
-    samplers: [
-        SamplerBinding::Required(b"u_Sampler\0"),
-        SamplerBinding::None,
-        SamplerBinding::None,
-        SamplerBinding::None,
-    ],   
+    uniform_blocks: &[UniformBlockBinding::Required(b"b_Locals\0")],
+    samplers: &[SamplerBinding::Required(b"u_Sampler\0")],
+    attributes: &[],
+    fragment_outputs: &[],
+    transform_feedback_varyings: TransformFeedbackVaryings::None,
 };
 
 const TRIANGLE_VERTICES: &'static [Vertex] = &[
@@ -107,9 +111,9 @@ fn main() {
     let mut vbuf = factory.empty_buffer(buf::Kind::Array, buf::Usage::StaticDraw);
     factory.initialize_buffer(&mut vbuf, TRIANGLE_VERTICES);
     let positions = buf::Accessor::new(vbuf, buf::format::F32(3), 0, 0);
-    let attributes = [Some(positions), None, None, None, None, None, None, None];
+    let attributes = [Some(positions.into())];
     let indices = None;
-    let vertex_array = factory.vertex_array(attributes, indices);
+    let vertex_array = factory.vertex_array(&attributes, indices);
 
     let tex = factory.texture2(1, 1, true, tex::format::U8::Rgba);
     factory.write_texture2(&tex, img::format::U8::Rgba, &[255, 255, 0, 0]);
@@ -124,8 +128,8 @@ fn main() {
     };
     let invocation = gpu::program::Invocation {
         program: &program,
-        uniforms: [Some(&ubuf), None, None, None],
-        samplers: [Some((&tex, sampler)), None, None, None],
+        uniforms: &[Some(ubuf.as_slice())],
+        samplers: &[Some((&tex, sampler).into())],
     };
 
     let mut running = true;
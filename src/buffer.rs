@@ -2,7 +2,8 @@
 
 use gl;
 use queue;
-use std::{cmp, fmt, hash, ops, sync};
+use std::{cmp, fmt, hash, marker, mem, ops, slice, sync};
+use std::sync::atomic;
 
 #[doc(inline)]
 pub use self::format::Format;
@@ -24,6 +25,24 @@ pub enum Kind {
 
     /// Corresponds to `GL_TEXTURE_BUFFER`.
     Texture,
+
+    /// Corresponds to `GL_PIXEL_PACK_BUFFER`.
+    PixelPack,
+
+    /// Corresponds to `GL_PIXEL_UNPACK_BUFFER`.
+    PixelUnpack,
+
+    /// Corresponds to `GL_COPY_READ_BUFFER`.
+    CopyRead,
+
+    /// Corresponds to `GL_COPY_WRITE_BUFFER`.
+    CopyWrite,
+
+    /// Corresponds to `GL_TRANSFORM_FEEDBACK_BUFFER`.
+    TransformFeedback,
+
+    /// Corresponds to `GL_DRAW_INDIRECT_BUFFER`.
+    DrawIndirect,
 }
 
 impl Kind {
@@ -34,18 +53,57 @@ impl Kind {
             Kind::Index => gl::ELEMENT_ARRAY_BUFFER,
             Kind::Uniform => gl::UNIFORM_BUFFER,
             Kind::Texture => gl::TEXTURE_BUFFER,
+            Kind::PixelPack => gl::PIXEL_PACK_BUFFER,
+            Kind::PixelUnpack => gl::PIXEL_UNPACK_BUFFER,
+            Kind::CopyRead => gl::COPY_READ_BUFFER,
+            Kind::CopyWrite => gl::COPY_WRITE_BUFFER,
+            Kind::TransformFeedback => gl::TRANSFORM_FEEDBACK_BUFFER,
+            Kind::DrawIndirect => gl::DRAW_INDIRECT_BUFFER,
         }
     }
 }
 
 /// A buffer data usage hint.
+///
+/// The first part specifies the frequency of access:
+///
+/// * `Static` - The contents are specified once and used many times.
+/// * `Dynamic` - The contents are specified repeatedly and used many times.
+/// * `Stream` - The contents are specified once and used at most a few times.
+///
+/// The second part specifies the nature of access:
+///
+/// * `Draw` - The contents are written by the application and read by GL.
+/// * `Read` - The contents are written by GL and read by the application.
+/// * `Copy` - The contents are written by GL and read by GL.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Usage {
     /// Corresponds to `GL_STATIC_DRAW`.
     StaticDraw,
 
+    /// Corresponds to `GL_STATIC_READ`.
+    StaticRead,
+
+    /// Corresponds to `GL_STATIC_COPY`.
+    StaticCopy,
+
     /// Corresponds to `GL_DYNAMIC_DRAW`.
     DynamicDraw,
+
+    /// Corresponds to `GL_DYNAMIC_READ`.
+    DynamicRead,
+
+    /// Corresponds to `GL_DYNAMIC_COPY`.
+    DynamicCopy,
+
+    /// Corresponds to `GL_STREAM_DRAW`.
+    StreamDraw,
+
+    /// Corresponds to `GL_STREAM_READ`.
+    StreamRead,
+
+    /// Corresponds to `GL_STREAM_COPY`.
+    StreamCopy,
 }
 
 impl Usage {
@@ -53,7 +111,14 @@ impl Usage {
     pub(crate) fn as_gl_enum(self) -> u32 {
         match self {
             Usage::StaticDraw => gl::STATIC_DRAW,
+            Usage::StaticRead => gl::STATIC_READ,
+            Usage::StaticCopy => gl::STATIC_COPY,
             Usage::DynamicDraw => gl::DYNAMIC_DRAW,
+            Usage::DynamicRead => gl::DYNAMIC_READ,
+            Usage::DynamicCopy => gl::DYNAMIC_COPY,
+            Usage::StreamDraw => gl::STREAM_DRAW,
+            Usage::StreamRead => gl::STREAM_READ,
+            Usage::StreamCopy => gl::STREAM_COPY,
         }
     }
 }
@@ -79,12 +144,17 @@ pub struct Buffer {
     /// The type of buffer, e.g. a vertex buffer.
     kind: Kind,
 
-    /// The number of bytes held by the buffer.
-    size: usize,
+    /// The number of bytes held by the buffer, shared by all clones.
+    size: sync::Arc<atomic::AtomicUsize>,
 
     /// Data usage hint.
     usage: Usage,
 
+    /// Set while a [`Mapping`] of the buffer exists, shared by all clones.
+    ///
+    /// [`Mapping`]: struct.Mapping.html
+    mapped: sync::Arc<atomic::AtomicBool>,
+
     /// Returns the buffer back to the factory upon destruction.
     destructor: sync::Arc<Destructor>,
 }
@@ -102,10 +172,10 @@ impl Buffer {
             destructor: sync::Arc::new(Destructor { id, tx }),
             id,
             kind,
-            size,
+            size: sync::Arc::new(atomic::AtomicUsize::new(size)),
             usage,
+            mapped: sync::Arc::new(atomic::AtomicBool::new(false)),
         }
-        
     }
 
     /// Returns the OpenGL buffer ID.
@@ -113,9 +183,21 @@ impl Buffer {
         self.id
     }
 
-    /// Sets the buffer size.
-    pub(crate) fn set_size(&mut self, size: usize) {
-        self.size = size;
+    /// Sets the size of the buffer and all of its clones.
+    pub(crate) fn set_size(&self, size: usize) {
+        self.size.store(size, atomic::Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the buffer is currently mapped into client memory.
+    ///
+    /// A mapped buffer may not be used by the GPU until it is unmapped.
+    pub fn is_mapped(&self) -> bool {
+        self.mapped.load(atomic::Ordering::SeqCst)
+    }
+
+    /// Marks the buffer and all of its clones as (un)mapped.
+    pub(crate) fn set_mapped(&self, mapped: bool) {
+        self.mapped.store(mapped, atomic::Ordering::SeqCst);
     }
 
     /// Returns the buffer kind.
@@ -125,7 +207,7 @@ impl Buffer {
 
     /// Returns the number of bytes this buffer contains.
     pub fn size(&self) -> usize {
-        self.size
+        self.size.load(atomic::Ordering::SeqCst)
     }
 
     /// Returns the buffer data usage hint.
@@ -137,7 +219,7 @@ impl Buffer {
     ///
     /// [`Slice`]: buffer/struct.Slice.html
     pub fn as_slice(&self) -> Slice {
-        Slice::new(self, 0, self.size)
+        Slice::new(self, 0, self.size())
     }
 
     /// Creates a slice into a [`Buffer`].
@@ -169,7 +251,7 @@ impl fmt::Debug for Buffer {
         Buffer {
             id: self.id,
             kind: self.kind,
-            size: self.size,
+            size: self.size(),
             usage: self.usage,
         }.fmt(f)
     }
@@ -181,6 +263,314 @@ impl hash::Hash for Buffer {
     }
 }
 
+/// Specifies how the contents of a mapped buffer may be accessed.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Access {
+    /// The mapping may only be read from.
+    Read,
+
+    /// The mapping may only be written to. Previous contents of the
+    /// mapped range are discarded.
+    Write,
+
+    /// The mapping may be read from and written to.
+    ReadWrite,
+}
+
+impl Access {
+    /// Returns the equivalent `glMapBufferRange` access bitfield.
+    pub(crate) fn as_gl_bitfield(self) -> u32 {
+        match self {
+            Access::Read => gl::MAP_READ_BIT,
+            Access::Write => gl::MAP_WRITE_BIT | gl::MAP_INVALIDATE_RANGE_BIT,
+            Access::ReadWrite => gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
+        }
+    }
+
+    /// Returns `true` if the mapping may be read from.
+    pub fn readable(self) -> bool {
+        self != Access::Write
+    }
+
+    /// Returns `true` if the mapping may be written to.
+    pub fn writable(self) -> bool {
+        self != Access::Read
+    }
+}
+
+/// A region of buffer memory mapped into the client address space.
+///
+/// The mapping may be sent to and accessed from another thread, but it
+/// must be returned to [`Factory::unmap_buffer`] on the rendering thread
+/// before the buffer is used by any other GPU operation.
+///
+/// [`Factory::unmap_buffer`]: ../struct.Factory.html#method.unmap_buffer
+pub struct Mapping {
+    /// The mapped buffer, kept alive while mapped.
+    buffer: Buffer,
+
+    /// The client address of the first mapped byte.
+    ptr: *mut u8,
+
+    /// The byte offset of the mapped range into the buffer.
+    offset: usize,
+
+    /// The number of bytes mapped.
+    length: usize,
+
+    /// Permitted access.
+    access: Access,
+}
+
+unsafe impl Send for Mapping {}
+
+impl Mapping {
+    /// Constructor.
+    pub(crate) fn new(
+        buffer: Buffer,
+        ptr: *mut u8,
+        offset: usize,
+        length: usize,
+        access: Access,
+    ) -> Self {
+        Self { buffer, ptr, offset, length, access }
+    }
+
+    /// Returns the mapped buffer.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Returns the byte offset of the mapped range into the buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the number of bytes mapped.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns the permitted access.
+    pub fn access(&self) -> Access {
+        self.access
+    }
+
+    /// Returns the mapped bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        assert!(self.access.readable(), "mapping is not readable");
+        unsafe { slice::from_raw_parts(self.ptr, self.length) }
+    }
+
+    /// Returns the mapped bytes for writing.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        assert!(self.access.writable(), "mapping is not writable");
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.length) }
+    }
+
+    /// Copy `data` into the mapping starting at byte `offset`.
+    pub fn write<T: Pod>(&mut self, offset: usize, data: &[T]) {
+        let size = data.len() * mem::size_of::<T>();
+        let bytes = unsafe {
+            slice::from_raw_parts(data.as_ptr() as *const u8, size)
+        };
+        self.as_bytes_mut()[offset .. offset + size].copy_from_slice(bytes);
+    }
+}
+
+impl fmt::Debug for Mapping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[derive(Debug)]
+        struct Mapping<'a> {
+            buffer: &'a Buffer,
+            offset: usize,
+            length: usize,
+            access: Access,
+        }
+
+        Mapping {
+            buffer: &self.buffer,
+            offset: self.offset,
+            length: self.length,
+            access: self.access,
+        }.fmt(f)
+    }
+}
+
+/// Marker for types that may be copied byte-for-byte into GPU memory.
+///
+/// Implementors must be `#[repr(C)]` (or primitive), contain no padding
+/// bytes, and contain no pointers or references.
+///
+/// ```rust
+/// #[repr(C)]
+/// #[derive(Clone, Copy)]
+/// struct Vertex {
+///     position: [f32; 3],
+///     normal: [f32; 3],
+/// }
+///
+/// unsafe impl gpu::buffer::Pod for Vertex {}
+/// ```
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ( $($ty:ty,)* ) => {
+        $(
+            unsafe impl Pod for $ty {}
+            unsafe impl Pod for [$ty; 1] {}
+            unsafe impl Pod for [$ty; 2] {}
+            unsafe impl Pod for [$ty; 3] {}
+            unsafe impl Pod for [$ty; 4] {}
+            unsafe impl Pod for [[$ty; 2]; 2] {}
+            unsafe impl Pod for [[$ty; 3]; 3] {}
+            unsafe impl Pod for [[$ty; 4]; 4] {}
+        )*
+    };
+}
+
+impl_pod!(
+    u8,
+    u16,
+    u32,
+    u64,
+    i8,
+    i16,
+    i32,
+    i64,
+    f32,
+    f64,
+);
+
+/// A [`Buffer`] holding a contiguous array of `T`.
+///
+/// [`Buffer`]: struct.Buffer.html
+pub struct TypedBuffer<T: Pod> {
+    /// The underlying untyped buffer.
+    buffer: Buffer,
+
+    /// Marks the element type.
+    marker: marker::PhantomData<T>,
+}
+
+impl<T: Pod> TypedBuffer<T> {
+    /// Constructor.
+    pub(crate) fn new(buffer: Buffer) -> Self {
+        Self {
+            buffer,
+            marker: marker::PhantomData,
+        }
+    }
+
+    /// Returns the underlying untyped buffer.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Returns a mutable reference to the underlying untyped buffer.
+    pub(crate) fn buffer_mut(&mut self) -> &mut Buffer {
+        &mut self.buffer
+    }
+
+    /// Consumes the typed buffer, returning the underlying untyped buffer.
+    pub fn into_buffer(self) -> Buffer {
+        self.buffer
+    }
+
+    /// Returns the buffer kind.
+    pub fn kind(&self) -> Kind {
+        self.buffer.kind
+    }
+
+    /// Returns the number of `T` elements this buffer contains.
+    pub fn size(&self) -> usize {
+        self.buffer.size() / mem::size_of::<T>()
+    }
+
+    /// Returns the buffer data usage hint.
+    pub fn usage(&self) -> Usage {
+        self.buffer.usage
+    }
+
+    /// Returns the byte distance between consecutive elements.
+    pub fn stride(&self) -> usize {
+        mem::size_of::<T>()
+    }
+
+    /// Returns a [`Slice`] covering the whole buffer.
+    ///
+    /// [`Slice`]: struct.Slice.html
+    pub fn as_slice(&self) -> Slice {
+        self.buffer.as_slice()
+    }
+
+    /// Creates a slice of `count` elements starting at element `offset`.
+    pub fn slice(&self, offset: usize, count: usize) -> Slice {
+        let size = mem::size_of::<T>();
+        Slice::new(&self.buffer, offset * size, count * size)
+    }
+
+    /// Creates an [`Accessor`] reading a field of `T` at byte `offset`
+    /// from the start of each element.
+    ///
+    /// [`Accessor`]: struct.Accessor.html
+    pub fn accessor<F: Into<Format>>(&self, format: F, offset: usize) -> Accessor {
+        let format = format.into();
+        assert!(
+            offset + format.byte_size() <= mem::size_of::<T>(),
+            "accessor field lies outside of the element type",
+        );
+        Accessor::new(self.buffer.clone(), format, offset, mem::size_of::<T>())
+    }
+
+    /// Creates an [`Accessor`] reading a field of `T` at byte `offset`
+    /// from the start of each element, advancing once every `divisor`
+    /// instances.
+    ///
+    /// [`Accessor`]: struct.Accessor.html
+    pub fn instanced_accessor<F: Into<Format>>(
+        &self,
+        format: F,
+        offset: usize,
+        divisor: u32,
+    ) -> Accessor {
+        let accessor = self.accessor(format, offset);
+        Accessor::instanced(
+            self.buffer.clone(),
+            accessor.format(),
+            offset,
+            accessor.stride(),
+            divisor,
+        )
+    }
+}
+
+impl<T: Pod> Clone for TypedBuffer<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.buffer.clone())
+    }
+}
+
+impl<T: Pod> cmp::PartialEq<Self> for TypedBuffer<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.buffer == other.buffer
+    }
+}
+
+impl<T: Pod> cmp::Eq for TypedBuffer<T> {}
+
+impl<T: Pod> fmt::Debug for TypedBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.buffer.fmt(f)
+    }
+}
+
+impl<T: Pod> hash::Hash for TypedBuffer<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.buffer.hash(state);
+    }
+}
+
 /// A contiguous sub-region of a [`Buffer`].
 ///
 /// [`Buffer`]: struct.Buffer.html
@@ -223,6 +613,12 @@ impl<'a> Slice<'a> {
     }
 }
 
+impl<'a> From<&'a Buffer> for Slice<'a> {
+    fn from(buffer: &'a Buffer) -> Self {
+        buffer.as_slice()
+    }
+}
+
 /// A formatted view into a [`Buffer`].
 ///
 /// [`Buffer`]: struct.Buffer.html
@@ -239,6 +635,10 @@ pub struct Accessor {
 
     /// The number of bytes between each element.
     stride: usize,
+
+    /// The number of instances drawn before advancing to the next element,
+    /// or zero to advance once per vertex.
+    divisor: u32,
 }
 
 impl Accessor {
@@ -248,12 +648,25 @@ impl Accessor {
         format: F,
         offset: usize,
         stride: usize,
+    ) -> Self {
+        Self::instanced(buffer, format, offset, stride, 0)
+    }
+
+    /// Constructor for a vertex attribute that advances once every
+    /// `divisor` instances rather than once per vertex.
+    pub fn instanced<F: Into<Format>>(
+        buffer: Buffer,
+        format: F,
+        offset: usize,
+        stride: usize,
+        divisor: u32,
     ) -> Self {
         Self {
             buffer,
             format: format.into(),
             offset,
             stride,
+            divisor,
         }
     }
 
@@ -276,16 +689,30 @@ impl Accessor {
     pub fn stride(&self) -> usize {
         self.stride
     }
+
+    /// Returns the instance divisor, or zero if the accessor advances once
+    /// per vertex.
+    pub fn divisor(&self) -> u32 {
+        self.divisor
+    }
 }
 
 /// Buffer format descriptors.
 pub mod format {
     use gl;
 
+    /// 16-bit floating point number.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct F16(pub u8);
+
     /// 32-bit floating point number.
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct F32(pub u8);
 
+    /// 64-bit floating point number.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct F64(pub u8);
+
     /// Signed 8-bit integer.
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct I8(pub u8);
@@ -294,6 +721,10 @@ pub mod format {
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct I8Norm(pub u8);
 
+    /// Signed 8-bit integer converted to floating point.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct I8Scaled(pub u8);
+
     /// Signed 16-bit integer.
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct I16(pub u8);
@@ -302,10 +733,18 @@ pub mod format {
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct I16Norm(pub u8);
 
+    /// Signed 16-bit integer converted to floating point.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct I16Scaled(pub u8);
+
     /// Signed 32-bit integer.
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct I32(pub u8);
 
+    /// Signed 32-bit integer converted to floating point.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct I32Scaled(pub u8);
+
     /// Unsigned 8-bit integer.
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct U8(pub u8);
@@ -314,6 +753,10 @@ pub mod format {
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct U8Norm(pub u8);
 
+    /// Unsigned 8-bit integer converted to floating point.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct U8Scaled(pub u8);
+
     /// Unsigned 16-bit integer.
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct U16(pub u8);
@@ -322,58 +765,170 @@ pub mod format {
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct U16Norm(pub u8);
 
+    /// Unsigned 16-bit integer converted to floating point.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct U16Scaled(pub u8);
+
     /// Unsigned 32-bit integer.
     #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     pub struct U32(pub u8);
 
+    /// Unsigned 32-bit integer converted to floating point.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct U32Scaled(pub u8);
+
+    /// Four signed integers packed into 32 bits, with 10 bits for each of
+    /// x, y, and z and 2 bits for w, converted to floating point.
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct I2_10_10_10Rev;
+
+    /// Four signed normalized rationals packed into 32 bits, with 10 bits
+    /// for each of x, y, and z and 2 bits for w.
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct I2_10_10_10RevNorm;
+
+    /// Four unsigned integers packed into 32 bits, with 10 bits for each of
+    /// x, y, and z and 2 bits for w, converted to floating point.
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct U2_10_10_10Rev;
+
+    /// Four unsigned normalized rationals packed into 32 bits, with 10 bits
+    /// for each of x, y, and z and 2 bits for w.
+    #[allow(non_camel_case_types)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct U2_10_10_10RevNorm;
+
+    /// Three unsigned floating point numbers packed into 32 bits, with 11
+    /// bits for each of x and y and 10 bits for z.
+    ///
+    /// Requires OpenGL 4.4 or `GL_ARB_vertex_type_10f_11f_11f_rev`.
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub struct U10F11F11FRev;
+
+    /// Determines how attribute data is presented to a shader.
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub enum Class {
+        /// Read as `float`, `vecN`, etc. through `glVertexAttribPointer`.
+        Float,
+
+        /// Read as `int`, `uint`, `ivecN`, `uvecN`, etc. through
+        /// `glVertexAttribIPointer`.
+        Integer,
+
+        /// Read as `double`, `dvecN`, etc. through `glVertexAttribLPointer`.
+        Double,
+    }
+
     /// Describes the data format of an individual item in an accessor.
     #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    #[allow(non_camel_case_types)]
     pub enum Format {
+        /// 16-bit floating point number.
+        F16(u8),
+
         /// 32-bit floating point number.
         F32(u8),
 
+        /// 64-bit floating point number.
+        F64(u8),
+
         /// Signed 8-bit integer.
         I8(u8),
 
         /// Signed normalized 8-bit rational.
         I8Norm(u8),
 
+        /// Signed 8-bit integer converted to floating point.
+        I8Scaled(u8),
+
         /// Signed 16-bit integer.
         I16(u8),
 
         /// Signed normalized 16-bit rational.
         I16Norm(u8),
 
+        /// Signed 16-bit integer converted to floating point.
+        I16Scaled(u8),
+
         /// Signed 32-bit integer.
         I32(u8),
 
+        /// Signed 32-bit integer converted to floating point.
+        I32Scaled(u8),
+
         /// Unsigned 8-bit integer.
         U8(u8),
 
         /// Unsigned normalized 8-bit rational.
         U8Norm(u8),
 
+        /// Unsigned 8-bit integer converted to floating point.
+        U8Scaled(u8),
+
         /// Unsigned 16-bit integer.
         U16(u8),
 
         /// Unsigned normalized 16-bit rational.
         U16Norm(u8),
 
+        /// Unsigned 16-bit integer converted to floating point.
+        U16Scaled(u8),
+
         /// Unsigned 32-bit integer.
         U32(u8),
+
+        /// Unsigned 32-bit integer converted to floating point.
+        U32Scaled(u8),
+
+        /// Four signed integers packed into 32 bits, converted to floating
+        /// point.
+        I2_10_10_10Rev,
+
+        /// Four signed normalized rationals packed into 32 bits.
+        I2_10_10_10RevNorm,
+
+        /// Four unsigned integers packed into 32 bits, converted to
+        /// floating point.
+        U2_10_10_10Rev,
+
+        /// Four unsigned normalized rationals packed into 32 bits.
+        U2_10_10_10RevNorm,
+
+        /// Three unsigned floating point numbers packed into 32 bits.
+        U10F11F11FRev,
     }
 
     impl Format {
         /// Returns the corresponding GL data type enumeration constant.
         pub(crate) fn gl_data_type(self) -> u32 {
             match self {
+                Format::F16(_) => gl::HALF_FLOAT,
                 Format::F32(_) => gl::FLOAT,
-                Format::I8(_) | Format::I8Norm(_) => gl::BYTE,
-                Format::I16(_) | Format::I16Norm(_) => gl::SHORT,
-                Format::I32(_) => gl::INT,
-                Format::U8(_) | Format::U8Norm(_) => gl::UNSIGNED_BYTE,
-                Format::U16(_) | Format::U16Norm(_) => gl::UNSIGNED_SHORT,
-                Format::U32(_) => gl::UNSIGNED_INT,
+                Format::F64(_) => gl::DOUBLE,
+                Format::I8(_) |
+                Format::I8Norm(_) |
+                Format::I8Scaled(_) => gl::BYTE,
+                Format::I16(_) |
+                Format::I16Norm(_) |
+                Format::I16Scaled(_) => gl::SHORT,
+                Format::I32(_) |
+                Format::I32Scaled(_) => gl::INT,
+                Format::U8(_) |
+                Format::U8Norm(_) |
+                Format::U8Scaled(_) => gl::UNSIGNED_BYTE,
+                Format::U16(_) |
+                Format::U16Norm(_) |
+                Format::U16Scaled(_) => gl::UNSIGNED_SHORT,
+                Format::U32(_) |
+                Format::U32Scaled(_) => gl::UNSIGNED_INT,
+                Format::I2_10_10_10Rev |
+                Format::I2_10_10_10RevNorm => gl::INT_2_10_10_10_REV,
+                Format::U2_10_10_10Rev |
+                Format::U2_10_10_10RevNorm => gl::UNSIGNED_INT_2_10_10_10_REV,
+                Format::U10F11F11FRev => gl::UNSIGNED_INT_10F_11F_11F_REV,
             }
         }
 
@@ -384,24 +939,89 @@ pub mod format {
                 Format::I16Norm(_) => true,
                 Format::U8Norm(_) => true,
                 Format::U16Norm(_) => true,
+                Format::I2_10_10_10RevNorm => true,
+                Format::U2_10_10_10RevNorm => true,
                 _ => false,
             }
         }
 
+        /// Returns how the data is presented to a shader.
+        pub fn class(self) -> Class {
+            match self {
+                Format::F64(_) => Class::Double,
+                Format::I8(_) |
+                Format::I16(_) |
+                Format::I32(_) |
+                Format::U8(_) |
+                Format::U16(_) |
+                Format::U32(_) => Class::Integer,
+                _ => Class::Float,
+            }
+        }
+
+        /// Returns true if the data is read by shaders as integers.
+        pub fn is_integer(self) -> bool {
+            self.class() == Class::Integer
+        }
+
+        /// Returns the number of bytes occupied by one item.
+        pub fn byte_size(self) -> usize {
+            let component = match self {
+                Format::I2_10_10_10Rev |
+                Format::I2_10_10_10RevNorm |
+                Format::U2_10_10_10Rev |
+                Format::U2_10_10_10RevNorm |
+                Format::U10F11F11FRev => return 4,
+                Format::I8(_) |
+                Format::I8Norm(_) |
+                Format::I8Scaled(_) |
+                Format::U8(_) |
+                Format::U8Norm(_) |
+                Format::U8Scaled(_) => 1,
+                Format::F16(_) |
+                Format::I16(_) |
+                Format::I16Norm(_) |
+                Format::I16Scaled(_) |
+                Format::U16(_) |
+                Format::U16Norm(_) |
+                Format::U16Scaled(_) => 2,
+                Format::F32(_) |
+                Format::I32(_) |
+                Format::I32Scaled(_) |
+                Format::U32(_) |
+                Format::U32Scaled(_) => 4,
+                Format::F64(_) => 8,
+            };
+            component * self.size()
+        }
+
         /// Returns the number of elements.
         pub fn size(self) -> usize {
             let size = match self {
+                Format::F16(size) => size,
                 Format::F32(size) => size,
+                Format::F64(size) => size,
                 Format::I8(size) => size,
                 Format::I8Norm(size) => size,
+                Format::I8Scaled(size) => size,
                 Format::I16(size) => size,
                 Format::I16Norm(size) => size,
+                Format::I16Scaled(size) => size,
                 Format::I32(size) => size,
+                Format::I32Scaled(size) => size,
                 Format::U8(size) => size,
                 Format::U8Norm(size) => size,
+                Format::U8Scaled(size) => size,
                 Format::U16(size) => size,
                 Format::U16Norm(size) => size,
+                Format::U16Scaled(size) => size,
                 Format::U32(size) => size,
+                Format::U32Scaled(size) => size,
+                Format::I2_10_10_10Rev |
+                Format::I2_10_10_10RevNorm |
+                Format::U2_10_10_10Rev |
+                Format::U2_10_10_10RevNorm => 4,
+                Format::U10F11F11FRev => 3,
             };
             match size {
                 1 | 2 | 3 | 4 => size as usize,
@@ -422,17 +1042,210 @@ pub mod format {
         };
     }
 
+    macro_rules! impl_from_packed_format {
+        ( $($ident:ident,)* ) => {
+            $(
+                impl From<$ident> for Format {
+                    fn from(_: $ident) -> Format {
+                        Format::$ident
+                    }
+                }
+            )*
+        };
+    }
+
     impl_from_format!(
+        F16,
         F32,
+        F64,
         I8,
         I8Norm,
+        I8Scaled,
         I16,
         I16Norm,
+        I16Scaled,
         I32,
+        I32Scaled,
         U8,
         U8Norm,
+        U8Scaled,
         U16,
         U16Norm,
+        U16Scaled,
         U32,
+        U32Scaled,
     );
+
+    impl_from_packed_format!(
+        I2_10_10_10Rev,
+        I2_10_10_10RevNorm,
+        U2_10_10_10Rev,
+        U2_10_10_10RevNorm,
+        U10F11F11FRev,
+    );
+}
+
+/// CPU-side packing of vertex data into the compact [`Format`]s.
+///
+/// [`Format`]: format/enum.Format.html
+pub mod pack {
+    /// Converts a number to a 16-bit float for the `F16` format, rounding
+    /// to nearest even.
+    pub fn f16(value: f32) -> u16 {
+        let sign = (value.to_bits() >> 16) & 0x8000;
+        (sign | small_float(value.abs(), 10)) as u16
+    }
+
+    /// Packs four signed integers in `-512 ..= 511` (x, y, z) and
+    /// `-2 ..= 1` (w) for the `I2_10_10_10Rev` format.
+    pub fn i2_10_10_10_rev(value: [i32; 4]) -> u32 {
+        (value[0] as u32 & 0x3FF) |
+            (value[1] as u32 & 0x3FF) << 10 |
+            (value[2] as u32 & 0x3FF) << 20 |
+            (value[3] as u32 & 0x3) << 30
+    }
+
+    /// Packs four rationals in `-1.0 ..= 1.0` for the `I2_10_10_10RevNorm`
+    /// format, e.g. a unit normal or a tangent with its handedness in w.
+    ///
+    /// Uses the GL 4.2+ conversion `f = max(c / 511, -1)`, which represents
+    /// zero exactly. Older drivers decode with `(2c + 1) / 1023` instead;
+    /// see [`i2_10_10_10_rev_norm_legacy`].
+    ///
+    /// [`i2_10_10_10_rev_norm_legacy`]: fn.i2_10_10_10_rev_norm_legacy.html
+    pub fn i2_10_10_10_rev_norm(value: [f32; 4]) -> u32 {
+        let snorm = |x: f32, max: f32| (x.max(-1.0).min(1.0) * max).round() as i32;
+        i2_10_10_10_rev([
+            snorm(value[0], 511.0),
+            snorm(value[1], 511.0),
+            snorm(value[2], 511.0),
+            snorm(value[3], 1.0),
+        ])
+    }
+
+    /// Packs four rationals in `-1.0 ..= 1.0` for the `I2_10_10_10RevNorm`
+    /// format as decoded by GL 3.3 to 4.1, i.e. `f = (2c + 1) / (2^b - 1)`.
+    ///
+    /// Zero is not representable and rounds to the smallest negative value.
+    pub fn i2_10_10_10_rev_norm_legacy(value: [f32; 4]) -> u32 {
+        let snorm = |x: f32, max: f32| {
+            ((x.max(-1.0).min(1.0) * max - 1.0) / 2.0).round() as i32
+        };
+        i2_10_10_10_rev([
+            snorm(value[0], 1023.0),
+            snorm(value[1], 1023.0),
+            snorm(value[2], 1023.0),
+            snorm(value[3], 3.0),
+        ])
+    }
+
+    /// Packs four unsigned integers in `0 ..= 1023` (x, y, z) and `0 ..= 3`
+    /// (w) for the `U2_10_10_10Rev` format.
+    pub fn u2_10_10_10_rev(value: [u32; 4]) -> u32 {
+        (value[0] & 0x3FF) |
+            (value[1] & 0x3FF) << 10 |
+            (value[2] & 0x3FF) << 20 |
+            (value[3] & 0x3) << 30
+    }
+
+    /// Packs four rationals in `0.0 ..= 1.0` for the `U2_10_10_10RevNorm`
+    /// format.
+    pub fn u2_10_10_10_rev_norm(value: [f32; 4]) -> u32 {
+        let unorm = |x: f32, max: f32| (x.max(0.0).min(1.0) * max).round() as u32;
+        u2_10_10_10_rev([
+            unorm(value[0], 1023.0),
+            unorm(value[1], 1023.0),
+            unorm(value[2], 1023.0),
+            unorm(value[3], 3.0),
+        ])
+    }
+
+    /// Packs three non-negative numbers for the `U10F11F11FRev` format.
+    ///
+    /// Negative numbers are clamped to zero.
+    pub fn u10f_11f_11f_rev(value: [f32; 3]) -> u32 {
+        let unsigned = |x: f32| if x.is_nan() { x } else { x.max(0.0) };
+        small_float(unsigned(value[0]), 6) |
+            small_float(unsigned(value[1]), 6) << 11 |
+            small_float(unsigned(value[2]), 5) << 22
+    }
+
+    /// Converts a non-negative number to an unsigned float with a 5-bit
+    /// exponent and the given number of mantissa bits, rounding to nearest
+    /// even.
+    fn small_float(value: f32, mantissa_bits: u32) -> u32 {
+        let bits = value.to_bits() & 0x7FFF_FFFF;
+        let exponent = (bits >> 23) as i32;
+        let mantissa = bits & 0x7F_FFFF;
+        let infinity = 0x1F << mantissa_bits;
+        if exponent == 0xFF {
+            // Infinity or NaN.
+            let nan = if mantissa != 0 { 1 << (mantissa_bits - 1) } else { 0 };
+            return infinity | nan;
+        }
+        // Rebias from the 8-bit to the 5-bit exponent.
+        let exponent = exponent - 127 + 15;
+        if exponent >= 0x1F {
+            return infinity;
+        }
+        let (value, shift) = if exponent > 0 {
+            ((exponent as u32) << 23 | mantissa, 23 - mantissa_bits)
+        } else if exponent >= -(mantissa_bits as i32) {
+            // Denormalized, with the implicit leading one made explicit.
+            (mantissa | 0x80_0000, (24 - mantissa_bits as i32 - exponent) as u32)
+        } else {
+            return 0;
+        };
+        let result = value >> shift;
+        let remainder = value & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        if remainder > halfway || (remainder == halfway && result & 1 == 1) {
+            result + 1
+        } else {
+            result
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn f16_values() {
+            assert_eq!(f16(0.0), 0x0000);
+            assert_eq!(f16(-0.0), 0x8000);
+            assert_eq!(f16(1.0), 0x3C00);
+            assert_eq!(f16(-2.0), 0xC000);
+            assert_eq!(f16(0.333333), 0x3555);
+            assert_eq!(f16(65504.0), 0x7BFF);
+            assert_eq!(f16(1.0e6), 0x7C00);
+            assert_eq!(f16(6.1035156e-5), 0x0400);
+            assert_eq!(f16(3.0517578e-5), 0x0200);
+            assert_eq!(f16(5.96e-8), 0x0001);
+            assert_eq!(f16(::std::f32::NAN) & 0x7E00, 0x7E00);
+        }
+
+        #[test]
+        fn i2_10_10_10_rev_values() {
+            assert_eq!(i2_10_10_10_rev([511, -512, -1, -2]), 0xBFF801FF);
+            assert_eq!(i2_10_10_10_rev_norm([1.0, -1.0, 0.0, -1.0]), 0xC00805FF);
+            assert_eq!(i2_10_10_10_rev_norm([2.0, -2.0, 0.0, 1.0]), 0x400805FF);
+            assert_eq!(i2_10_10_10_rev_norm_legacy([1.0, -1.0, 0.0, -1.0]), 0xBFF801FF);
+        }
+
+        #[test]
+        fn u2_10_10_10_rev_values() {
+            assert_eq!(u2_10_10_10_rev([1023, 0, 1023, 3]), 0xFFF003FF);
+            assert_eq!(u2_10_10_10_rev_norm([1.0, 0.0, 1.0, 1.0]), 0xFFF003FF);
+            assert_eq!(u2_10_10_10_rev_norm([-1.0, 0.5, 2.0, 0.0]), 0x3FF80000);
+        }
+
+        #[test]
+        fn u10f_11f_11f_rev_values() {
+            assert_eq!(u10f_11f_11f_rev([0.0, 0.0, 0.0]), 0);
+            assert_eq!(u10f_11f_11f_rev([1.0, 1.0, 1.0]), 0x781E03C0);
+            assert_eq!(u10f_11f_11f_rev([-1.0, 0.0, 0.0]), 0);
+            assert_eq!(u10f_11f_11f_rev([1.0e6, 0.0, 0.0]), 0x7C0);
+        }
+    }
 }
@@ -1,10 +1,14 @@
 //! Draw call dispatch.
 
+use buffer::{self, Pod};
 use gl;
 
 /// Primitive topology.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Primitive {
+    /// `[v0, v1, v2, ...]`.
+    Points,
+
     /// `[(v0, v1, v2), (v0, v1, v2), ...]`.
     Triangles,
 
@@ -13,6 +17,10 @@ pub enum Primitive {
     /// * In total, n - 2 triangles are drawn.
     TriangleStrip,
 
+    /// `[center, v1, v2, ..., vn]` where vertices 0, n + 1, and n + 2
+    /// define triangle n.
+    TriangleFan,
+
     /// `[(start, end), (start, end), ...]`.
     Lines,
 
@@ -21,39 +29,264 @@ pub enum Primitive {
 
     /// `[start, end/start, ..., end/start, end; <implicit_start>]`.
     LineLoop,
+
+    /// `[(a, start, end, b), (a, start, end, b), ...]` where `a` and `b`
+    /// are adjacent vertices visible only to geometry shaders.
+    ///
+    /// Requires a program with a geometry shader.
+    LinesAdjacency,
+
+    /// `[a, start, end/start, ..., end/start, end, b]` where `a` and `b`
+    /// are adjacent vertices visible only to geometry shaders.
+    ///
+    /// Requires a program with a geometry shader.
+    LineStripAdjacency,
+
+    /// `[(v0, a0, v1, a1, v2, a2), ...]` where `a0`, `a1`, and `a2` are
+    /// adjacent vertices visible only to geometry shaders.
+    ///
+    /// Requires a program with a geometry shader.
+    TrianglesAdjacency,
+
+    /// Every even vertex continues the triangle strip and every odd
+    /// vertex is an adjacent vertex visible only to geometry shaders.
+    ///
+    /// Requires a program with a geometry shader.
+    TriangleStripAdjacency,
 }
 
 impl Primitive {
     pub(crate) fn as_gl_enum(self) -> u32 {
         match self {
+            Primitive::Points => gl::POINTS,
             Primitive::Triangles => gl::TRIANGLES,
             Primitive::TriangleStrip => gl::TRIANGLE_STRIP,
+            Primitive::TriangleFan => gl::TRIANGLE_FAN,
             Primitive::Lines => gl::LINES,
             Primitive::LineStrip => gl::LINE_STRIP,
             Primitive::LineLoop => gl::LINE_LOOP,
+            Primitive::LinesAdjacency => gl::LINES_ADJACENCY,
+            Primitive::LineStripAdjacency => gl::LINE_STRIP_ADJACENCY,
+            Primitive::TrianglesAdjacency => gl::TRIANGLES_ADJACENCY,
+            Primitive::TriangleStripAdjacency => gl::TRIANGLE_STRIP_ADJACENCY,
+        }
+    }
+
+    /// Returns `true` for the adjacency topologies, which may only be drawn
+    /// by programs with a geometry shader.
+    pub fn is_adjacency(self) -> bool {
+        match self {
+            Primitive::LinesAdjacency |
+            Primitive::LineStripAdjacency |
+            Primitive::TrianglesAdjacency |
+            Primitive::TriangleStripAdjacency => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the primitive mode passed to `glBeginTransformFeedback` for
+    /// programs without a geometry shader.
+    pub(crate) fn transform_feedback_mode(self) -> u32 {
+        match self {
+            Primitive::Points => gl::POINTS,
+            Primitive::Triangles |
+            Primitive::TriangleStrip |
+            Primitive::TriangleFan => gl::TRIANGLES,
+            Primitive::Lines |
+            Primitive::LineStrip |
+            Primitive::LineLoop => gl::LINES,
+            Primitive::LinesAdjacency |
+            Primitive::LineStripAdjacency |
+            Primitive::TrianglesAdjacency |
+            Primitive::TriangleStripAdjacency => {
+                unreachable!("adjacency primitives require a geometry shader")
+            }
         }
     }
 }
 
 /// Draw call kind.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum Kind {
+pub enum Kind<'a> {
     /// Draw arrays once.
     Arrays,
 
     /// Draw arrays many times.
     ArraysInstanced(usize),
 
+    /// Draw arrays many times, with instanced attributes starting from
+    /// the given base instance.
+    ///
+    /// Requires OpenGL 4.2 or `GL_ARB_base_instance`.
+    ArraysInstancedBaseInstance {
+        /// Number of instances to draw.
+        instances: usize,
+
+        /// The first instance to read instanced attributes from.
+        base_instance: usize,
+    },
+
     /// Draw elements once.
     Elements,
 
+    /// Draw elements once, adding `base_vertex` to every index.
+    ElementsBaseVertex {
+        /// Value added to each index before fetching vertex attributes.
+        base_vertex: i32,
+    },
+
+    /// Draw elements once, promising every index lies within
+    /// `start ..= end`.
+    ElementsRange {
+        /// The minimum index value.
+        start: usize,
+
+        /// The maximum index value.
+        end: usize,
+    },
+
+    /// Draw elements once, promising every index lies within
+    /// `start ..= end`, and adding `base_vertex` to every index.
+    ///
+    /// The range applies to indices before `base_vertex` is added.
+    ElementsRangeBaseVertex {
+        /// The minimum index value.
+        start: usize,
+
+        /// The maximum index value.
+        end: usize,
+
+        /// Value added to each index before fetching vertex attributes.
+        base_vertex: i32,
+    },
+
     /// Draw elements many times.
     ElementsInstanced(usize),
+
+    /// Draw elements many times, adding `base_vertex` to every index.
+    ElementsInstancedBaseVertex {
+        /// Number of instances to draw.
+        instances: usize,
+
+        /// Value added to each index before fetching vertex attributes.
+        base_vertex: i32,
+    },
+
+    /// Draw elements many times, with instanced attributes starting from
+    /// the given base instance.
+    ///
+    /// Requires OpenGL 4.2 or `GL_ARB_base_instance`.
+    ElementsInstancedBaseInstance {
+        /// Number of instances to draw.
+        instances: usize,
+
+        /// The first instance to read instanced attributes from.
+        base_instance: usize,
+    },
+
+    /// Draw arrays with the parameters of an [`ArraysIndirectCommand`]
+    /// read from a buffer at the start of the slice.
+    ///
+    /// The draw call offset and count are ignored.
+    /// Requires OpenGL 4.0 or `GL_ARB_draw_indirect`.
+    ///
+    /// [`ArraysIndirectCommand`]: struct.ArraysIndirectCommand.html
+    ArraysIndirect(buffer::Slice<'a>),
+
+    /// Draw elements with the parameters of an
+    /// [`ElementsIndirectCommand`] read from a buffer at the start of the
+    /// slice.
+    ///
+    /// The draw call offset and count are ignored.
+    /// Requires OpenGL 4.0 or `GL_ARB_draw_indirect`.
+    ///
+    /// [`ElementsIndirectCommand`]: struct.ElementsIndirectCommand.html
+    ElementsIndirect(buffer::Slice<'a>),
+
+    /// Draw arrays once for every [`ArraysIndirectCommand`] in the slice.
+    ///
+    /// The draw call offset and count are ignored.
+    /// Requires OpenGL 4.3 or `GL_ARB_multi_draw_indirect`.
+    ///
+    /// [`ArraysIndirectCommand`]: struct.ArraysIndirectCommand.html
+    MultiArraysIndirect {
+        /// The commands to execute.
+        commands: buffer::Slice<'a>,
+
+        /// The number of bytes between consecutive commands, or zero if
+        /// the commands are tightly packed.
+        stride: usize,
+    },
+
+    /// Draw elements once for every [`ElementsIndirectCommand`] in the
+    /// slice.
+    ///
+    /// The draw call offset and count are ignored.
+    /// Requires OpenGL 4.3 or `GL_ARB_multi_draw_indirect`.
+    ///
+    /// [`ElementsIndirectCommand`]: struct.ElementsIndirectCommand.html
+    MultiElementsIndirect {
+        /// The commands to execute.
+        commands: buffer::Slice<'a>,
+
+        /// The number of bytes between consecutive commands, or zero if
+        /// the commands are tightly packed.
+        stride: usize,
+    },
+}
+
+/// Parameters of an indirect arrays draw call, as laid out in a
+/// `GL_DRAW_INDIRECT_BUFFER`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ArraysIndirectCommand {
+    /// Number of vertices to draw.
+    pub count: u32,
+
+    /// Number of instances to draw.
+    pub instance_count: u32,
+
+    /// Where the vertices begin.
+    pub first: u32,
+
+    /// The first instance to read instanced attributes from.
+    ///
+    /// Must be zero unless OpenGL 4.2 or `GL_ARB_base_instance` is
+    /// supported.
+    pub base_instance: u32,
 }
 
+unsafe impl Pod for ArraysIndirectCommand {}
+
+/// Parameters of an indirect elements draw call, as laid out in a
+/// `GL_DRAW_INDIRECT_BUFFER`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ElementsIndirectCommand {
+    /// Number of elements to draw.
+    pub count: u32,
+
+    /// Number of instances to draw.
+    pub instance_count: u32,
+
+    /// Where the elements begin, in elements rather than bytes.
+    pub first_index: u32,
+
+    /// Value added to each index before fetching vertex attributes.
+    pub base_vertex: i32,
+
+    /// The first instance to read instanced attributes from.
+    ///
+    /// Must be zero unless OpenGL 4.2 or `GL_ARB_base_instance` is
+    /// supported.
+    pub base_instance: u32,
+}
+
+unsafe impl Pod for ElementsIndirectCommand {}
+
 /// A draw call command.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct DrawCall {
+pub struct DrawCall<'a> {
     /// Where the vertices/elements begin.
     pub offset: usize,
 
@@ -64,5 +297,52 @@ pub struct DrawCall {
     pub primitive: Primitive,
 
     /// Draw call kind.
-    pub kind: Kind,
+    pub kind: Kind<'a>,
+}
+
+/// A sub-range of vertices/elements drawn by a [`MultiDrawCall`].
+///
+/// [`MultiDrawCall`]: struct.MultiDrawCall.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Range {
+    /// Where the vertices/elements begin.
+    pub offset: usize,
+
+    /// Number of vertices/elements to draw.
+    pub count: usize,
+
+    /// Value added to each index before fetching vertex attributes.
+    ///
+    /// Only used by [`MultiKind::ElementsBaseVertex`].
+    ///
+    /// [`MultiKind::ElementsBaseVertex`]: enum.MultiKind.html#variant.ElementsBaseVertex
+    pub base_vertex: i32,
+}
+
+/// Multi-draw call kind.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MultiKind {
+    /// Draw arrays once per range.
+    Arrays,
+
+    /// Draw elements once per range.
+    Elements,
+
+    /// Draw elements once per range, adding the range base vertex to
+    /// every index.
+    ElementsBaseVertex,
+}
+
+/// A command drawing many ranges of the same vertex array with the same
+/// pipeline state and program invocation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MultiDrawCall<'a> {
+    /// The ranges of vertices/elements to draw.
+    pub ranges: &'a [Range],
+
+    /// The primitive topology.
+    pub primitive: Primitive,
+
+    /// Multi-draw call kind.
+    pub kind: MultiKind,
 }
@@ -3,18 +3,30 @@
 #![allow(dead_code)]
 
 use buffer;
+use fence;
 use framebuffer;
 use gl;
 use image;
 use program;
 use renderbuffer;
 use shader;
-use std::{ffi, mem, ptr, sync};
-use texture;
+use std::{cmp, ffi, mem, ptr, sync};
+use texture::{self, Readback, TexelFormat, TextureBuffer};
 use util;
 use vertex_array;
 
-use draw_call::{DrawCall, Kind};
+use buffer::{Pod, TypedBuffer};
+use buffer::format::Class;
+use draw_call::{
+    ArraysIndirectCommand,
+    DrawCall,
+    ElementsIndirectCommand,
+    Kind,
+    MultiDrawCall,
+    MultiKind,
+    Primitive,
+};
+use heap::Heap;
 use framebuffer::{
     ColorAttachment,
     DepthStencilAttachment,
@@ -22,19 +34,33 @@ use framebuffer::{
     ClearColor,
     ClearDepth,
     Framebuffer,
-    MAX_COLOR_ATTACHMENTS,
 };
 use program::{
+    ActiveAttribute,
+    AttributeBinding,
+    FragmentOutputBinding,
     Invocation,
+    SamplerSource,
+    TransformFeedback,
+    TransformFeedbackVaryings,
     UniformBlockBinding,
     SamplerBinding,
-    MAX_UNIFORM_BLOCKS,
-    MAX_SAMPLERS,
 };
-use pipeline::{PolygonMode, State, Viewport};
+use pipeline::{Blending, PolygonMode, PrimitiveRestart, State, Viewport};
 use queue::Queue;
 use renderbuffer::Renderbuffer;
-use {Buffer, Context, Program, Texture2, VertexArray};
+use vertex_array::{Attribute, Constant};
+use {Buffer, Context, Fence, Program, Texture2, VertexArray};
+
+/// Binding target used when transferring data to and from buffers.
+///
+/// The buffer's own target is avoided since binding, for example,
+/// `GL_ELEMENT_ARRAY_BUFFER` modifies the currently bound vertex array and
+/// binding `GL_PIXEL_UNPACK_BUFFER` changes the meaning of texture uploads.
+const TRANSFER_TARGET: u32 = gl::COPY_WRITE_BUFFER;
+
+/// Nanoseconds to block for in each `glClientWaitSync` call.
+const FENCE_WAIT_TIMEOUT: u64 = 1_000_000;
 
 #[derive(Clone)]
 struct Queues {
@@ -44,6 +70,7 @@ struct Queues {
     program: Queue<program::Destroyed>,
     framebuffer: Queue<framebuffer::Id>,
     renderbuffer: Queue<renderbuffer::Id>,
+    fence: Queue<fence::Id>,
 }
 
 /// OpenGL memory manager.
@@ -54,6 +81,49 @@ pub struct Factory {
 
     /// Destroyed OpenGL objects arrive here to be destroyed or recycled.
     queues: Queues,
+
+    /// Required byte alignment of uniform buffer binding offsets.
+    uniform_buffer_offset_alignment: usize,
+
+    /// Driver limit on the number of vertex attributes.
+    max_vertex_attributes: usize,
+
+    /// Driver limit on the number of uniform buffer binding points.
+    max_uniform_blocks: usize,
+
+    /// Driver limit on the number of texture units.
+    max_samplers: usize,
+
+    /// Driver limit on the number of framebuffer color attachments.
+    max_color_attachments: usize,
+
+    /// Driver limit on the number of separately captured transform
+    /// feedback varyings.
+    max_transform_feedback_separate_attributes: usize,
+
+    /// Whether the `2_10_10_10Rev` packed vertex formats are supported.
+    has_vertex_type_2_10_10_10_rev: bool,
+
+    /// Whether the `U10F11F11FRev` packed vertex format is supported.
+    has_vertex_type_10f_11f_11f_rev: bool,
+
+    /// Whether the single indirect draw calls are supported.
+    has_draw_indirect: bool,
+
+    /// Whether the multi indirect draw calls are supported.
+    has_multi_draw_indirect: bool,
+
+    /// Whether vertex attributes may have a non-zero instance divisor.
+    has_instanced_arrays: bool,
+
+    /// Whether the `*BaseInstance` draw calls are supported.
+    has_base_instance: bool,
+
+    /// Whether blend functions may be set per draw buffer.
+    has_draw_buffers_blend: bool,
+
+    /// Whether 64-bit vertex attributes are supported.
+    has_vertex_attrib_64bit: bool,
 }
 
 impl Factory {
@@ -61,8 +131,47 @@ impl Factory {
     pub fn new<F>(query_proc_address: F) -> Self
         where F: FnMut(&str) -> *const ()
     {
+        let backend = gl::Backend::load(query_proc_address);
+        let uniform_buffer_offset_alignment = backend
+            .get_integerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT) as usize;
+        let max_vertex_attributes = backend
+            .get_integerv(gl::MAX_VERTEX_ATTRIBS) as usize;
+        let max_uniform_blocks = backend
+            .get_integerv(gl::MAX_UNIFORM_BUFFER_BINDINGS) as usize;
+        let max_samplers = backend
+            .get_integerv(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS) as usize;
+        let max_color_attachments = cmp::min(
+            backend.get_integerv(gl::MAX_COLOR_ATTACHMENTS),
+            backend.get_integerv(gl::MAX_DRAW_BUFFERS),
+        ) as usize;
+        let max_transform_feedback_separate_attributes = backend
+            .get_integerv(gl::MAX_TRANSFORM_FEEDBACK_SEPARATE_ATTRIBS) as usize;
+        let version = (
+            backend.get_integerv(gl::MAJOR_VERSION),
+            backend.get_integerv(gl::MINOR_VERSION),
+        );
+        let extensions = (0 .. backend.get_integerv(gl::NUM_EXTENSIONS))
+            .map(|index| backend.get_string_i(gl::EXTENSIONS, index as u32))
+            .collect::<Vec<_>>();
+        let has_extension = |name: &str| extensions.iter().any(|ext| ext == name);
+        let has_vertex_type_2_10_10_10_rev = version >= (3, 3) ||
+            has_extension("GL_ARB_vertex_type_2_10_10_10_rev");
+        let has_vertex_type_10f_11f_11f_rev = version >= (4, 4) ||
+            has_extension("GL_ARB_vertex_type_10f_11f_11f_rev");
+        let has_draw_indirect = version >= (4, 0) ||
+            has_extension("GL_ARB_draw_indirect");
+        let has_multi_draw_indirect = version >= (4, 3) ||
+            has_extension("GL_ARB_multi_draw_indirect");
+        let has_instanced_arrays = version >= (3, 3) ||
+            has_extension("GL_ARB_instanced_arrays");
+        let has_base_instance = version >= (4, 2) ||
+            has_extension("GL_ARB_base_instance");
+        let has_draw_buffers_blend = version >= (4, 0) ||
+            has_extension("GL_ARB_draw_buffers_blend");
+        let has_vertex_attrib_64bit = version >= (4, 1) ||
+            has_extension("GL_ARB_vertex_attrib_64bit");
         Self {
-            backend: gl::Backend::load(query_proc_address),
+            backend,
             queues: Queues {
                 buffer: Queue::new(),
                 texture: Queue::new(),
@@ -70,8 +179,54 @@ impl Factory {
                 program: Queue::new(),
                 framebuffer: Queue::new(),
                 renderbuffer: Queue::new(),
+                fence: Queue::new(),
+            },
+            uniform_buffer_offset_alignment,
+            max_vertex_attributes,
+            max_uniform_blocks,
+            max_samplers,
+            max_color_attachments,
+            max_transform_feedback_separate_attributes,
+            has_vertex_type_2_10_10_10_rev,
+            has_vertex_type_10f_11f_11f_rev,
+            has_draw_indirect,
+            has_multi_draw_indirect,
+            has_instanced_arrays,
+            has_base_instance,
+            has_draw_buffers_blend,
+            has_vertex_attrib_64bit,
+        }
+    }
+
+    /// Delete the GL objects whose last handle has been dropped since the
+    /// previous collection.
+    ///
+    /// Called whenever buffers or fences are created and before drawing.
+    fn collect_garbage(&self) {
+        while let Some(id) = self.queues.buffer.next() {
+            self.backend.delete_buffer(id);
+        }
+        while let Some(id) = self.queues.texture.next() {
+            self.backend.delete_texture(id);
+        }
+        while let Some(id) = self.queues.vertex_array.next() {
+            self.backend.delete_vertex_array(id);
+        }
+        while let Some(destroyed) = self.queues.program.next() {
+            match destroyed {
+                program::Destroyed::Object(id) => self.backend.delete_shader(id),
+                program::Destroyed::Program(id) => self.backend.delete_program(id),
             }
         }
+        while let Some(id) = self.queues.framebuffer.next() {
+            self.backend.delete_framebuffer(id);
+        }
+        while let Some(id) = self.queues.renderbuffer.next() {
+            self.backend.delete_renderbuffer(id);
+        }
+        while let Some(id) = self.queues.fence.next() {
+            self.backend.delete_sync(id);
+        }
     }
 
     /// Clear the color buffer.
@@ -87,6 +242,8 @@ impl Factory {
         }
         match op.depth {
             ClearDepth::Yes { z } => {
+                // The depth write mask of the last draw also masks clears.
+                self.backend.depth_mask(true);
                 self.backend.clear_depth(z);
                 ops |= gl::DEPTH_BUFFER_BIT;
             }
@@ -98,23 +255,282 @@ impl Factory {
     /// (Re)-initialize the contents of a [`Buffer`].
     ///
     /// [`Buffer`]: buffer/struct.Buffer.html
-    pub fn initialize_buffer<T>(&self, buffer: &mut Buffer, data: &[T]) {
-        self.backend.bind_buffer(buffer.id(), buffer.kind().as_gl_enum());
+    pub fn initialize_buffer<T: Pod>(&self, buffer: &mut Buffer, data: &[T]) {
+        assert_unmapped(buffer);
+        self.backend.bind_buffer(buffer.id(), TRANSFER_TARGET);
         self.backend.buffer_data(
-            buffer.kind().as_gl_enum(),
+            TRANSFER_TARGET,
             data.len() * mem::size_of::<T>(),
             data.as_ptr() as *const _,
             buffer.usage().as_gl_enum(),
         );
-        self.backend.bind_buffer(0, buffer.kind().as_gl_enum());
+        self.backend.bind_buffer(0, TRANSFER_TARGET);
         buffer.set_size(data.len() * mem::size_of::<T>());
     }
 
     /// Overwrite part of a buffer.
-    pub fn overwrite_buffer<T>(&self, slice: buffer::Slice, data: &[T]) {
-        self.backend.bind_buffer(slice.id(), slice.kind().as_gl_enum());
-        self.backend.buffer_sub_data(slice.kind().as_gl_enum(), slice.offset(), slice.length(), data.as_ptr());
-        self.backend.bind_buffer(0, slice.kind().as_gl_enum());
+    pub fn overwrite_buffer<T: Pod>(&self, slice: buffer::Slice, data: &[T]) {
+        assert_unmapped(slice.buffer());
+        assert_eq!(
+            data.len() * mem::size_of::<T>(),
+            slice.length(),
+            "data size does not match slice length",
+        );
+        self.backend.bind_buffer(slice.id(), TRANSFER_TARGET);
+        self.backend.buffer_sub_data(TRANSFER_TARGET, slice.offset(), slice.length(), data.as_ptr());
+        self.backend.bind_buffer(0, TRANSFER_TARGET);
+    }
+
+    /// Read back part of a buffer.
+    ///
+    /// Buffers intended to be read back should be created with one of the
+    /// `*Read` usage hints.
+    pub fn read_buffer<T: Pod>(&self, slice: buffer::Slice, data: &mut [T]) {
+        assert_unmapped(slice.buffer());
+        assert_eq!(
+            data.len() * mem::size_of::<T>(),
+            slice.length(),
+            "data size does not match slice length",
+        );
+        self.backend.bind_buffer(slice.id(), TRANSFER_TARGET);
+        self.backend.get_buffer_sub_data(
+            TRANSFER_TARGET,
+            slice.offset(),
+            slice.length(),
+            data.as_mut_ptr(),
+        );
+        self.backend.bind_buffer(0, TRANSFER_TARGET);
+    }
+
+    /// Map part of a buffer into client memory.
+    ///
+    /// The returned [`Mapping`] may be filled or read from any thread, but
+    /// must be passed to [`unmap_buffer`] before the buffer is next used.
+    /// Until then, any factory method using the buffer or one of its clones
+    /// panics, since GL operations such as `glBufferData` would invalidate
+    /// the mapping. A mapping dropped without being unmapped leaves the
+    /// buffer unusable.
+    ///
+    /// [`Mapping`]: buffer/struct.Mapping.html
+    /// [`unmap_buffer`]: #method.unmap_buffer
+    pub fn map_buffer(
+        &self,
+        slice: buffer::Slice,
+        access: buffer::Access,
+    ) -> buffer::Mapping {
+        assert_unmapped(slice.buffer());
+        assert!(
+            slice.offset() + slice.length() <= slice.buffer().size(),
+            "buffer mapping out of bounds",
+        );
+        self.backend.bind_buffer(slice.id(), TRANSFER_TARGET);
+        let ptr = self.backend.map_buffer_range(
+            TRANSFER_TARGET,
+            slice.offset(),
+            slice.length(),
+            access.as_gl_bitfield(),
+        );
+        self.backend.bind_buffer(0, TRANSFER_TARGET);
+        assert!(!ptr.is_null(), "failed to map buffer");
+        slice.buffer().set_mapped(true);
+        buffer::Mapping::new(
+            slice.buffer().clone(),
+            ptr as *mut u8,
+            slice.offset(),
+            slice.length(),
+            access,
+        )
+    }
+
+    /// Unmap a buffer previously mapped with [`map_buffer`].
+    ///
+    /// Returns `false` if the buffer contents were corrupted while mapped,
+    /// in which case they must be re-initialized.
+    ///
+    /// [`map_buffer`]: #method.map_buffer
+    pub fn unmap_buffer(&self, mapping: buffer::Mapping) -> bool {
+        self.backend.bind_buffer(mapping.buffer().id(), TRANSFER_TARGET);
+        let result = self.backend.unmap_buffer(TRANSFER_TARGET);
+        self.backend.bind_buffer(0, TRANSFER_TARGET);
+        mapping.buffer().set_mapped(false);
+        result
+    }
+
+    /// (Re)-initialize the contents of a [`TypedBuffer`].
+    ///
+    /// [`TypedBuffer`]: buffer/struct.TypedBuffer.html
+    pub fn initialize_typed_buffer<T: Pod>(
+        &self,
+        buffer: &mut TypedBuffer<T>,
+        data: &[T],
+    ) {
+        self.initialize_buffer(buffer.buffer_mut(), data);
+    }
+
+    /// Overwrite `data.len()` elements of a [`TypedBuffer`] starting at
+    /// element `offset`.
+    ///
+    /// [`TypedBuffer`]: buffer/struct.TypedBuffer.html
+    pub fn overwrite_typed_buffer<T: Pod>(
+        &self,
+        buffer: &TypedBuffer<T>,
+        offset: usize,
+        data: &[T],
+    ) {
+        assert!(
+            offset + data.len() <= buffer.size(),
+            "typed buffer overwrite out of bounds",
+        );
+        self.overwrite_buffer(buffer.slice(offset, data.len()), data);
+    }
+
+    /// Copy the contents of one buffer region into another on the GPU.
+    ///
+    /// Both slices must have the same length and lie within their parent
+    /// buffers. If both slices refer to the same buffer then the regions
+    /// must not overlap.
+    pub fn copy_buffer(&self, src: buffer::Slice, dst: buffer::Slice) {
+        assert_unmapped(src.buffer());
+        assert_unmapped(dst.buffer());
+        assert_eq!(
+            src.length(),
+            dst.length(),
+            "buffer copy source and destination lengths differ",
+        );
+        assert!(
+            src.offset() + src.length() <= src.buffer().size(),
+            "buffer copy source out of bounds",
+        );
+        assert!(
+            dst.offset() + dst.length() <= dst.buffer().size(),
+            "buffer copy destination out of bounds",
+        );
+        if src.id() == dst.id() {
+            assert!(
+                src.offset() + src.length() <= dst.offset() ||
+                    dst.offset() + dst.length() <= src.offset(),
+                "buffer copy source and destination overlap",
+            );
+        }
+        self.backend.bind_buffer(src.id(), gl::COPY_READ_BUFFER);
+        self.backend.bind_buffer(dst.id(), gl::COPY_WRITE_BUFFER);
+        self.backend.copy_buffer_sub_data(
+            gl::COPY_READ_BUFFER,
+            gl::COPY_WRITE_BUFFER,
+            src.offset(),
+            dst.offset(),
+            src.length(),
+        );
+        self.backend.bind_buffer(0, gl::COPY_READ_BUFFER);
+        self.backend.bind_buffer(0, gl::COPY_WRITE_BUFFER);
+    }
+
+    /// Resize a [`Buffer`] to `size` bytes, preserving its contents.
+    ///
+    /// If the buffer grows, the new memory is uninitialized. If the buffer
+    /// shrinks, the trailing contents are discarded. The OpenGL buffer ID
+    /// is retained and the new size is seen by every clone, so vertex
+    /// arrays and texture buffers referring to the buffer remain valid.
+    ///
+    /// [`Buffer`]: buffer/struct.Buffer.html
+    pub fn resize_buffer(&self, buffer: &mut Buffer, size: usize) {
+        assert_unmapped(buffer);
+        let preserved = cmp::min(buffer.size(), size);
+        let temp = if preserved > 0 {
+            // The staging buffer is deleted by the next garbage collection
+            // once dropped at the end of this call.
+            let temp = self.uninitialized_buffer(
+                preserved,
+                buffer::Kind::CopyRead,
+                buffer::Usage::StreamCopy,
+            );
+            self.copy_buffer(buffer.slice(0, preserved), temp.as_slice());
+            Some(temp)
+        } else {
+            None
+        };
+        self.backend.bind_buffer(buffer.id(), TRANSFER_TARGET);
+        self.backend.buffer_data(
+            TRANSFER_TARGET,
+            size,
+            ptr::null() as *const (),
+            buffer.usage().as_gl_enum(),
+        );
+        self.backend.bind_buffer(0, TRANSFER_TARGET);
+        buffer.set_size(size);
+        if let Some(temp) = temp {
+            self.copy_buffer(temp.as_slice(), buffer.slice(0, preserved));
+        }
+    }
+
+    /// Returns `true` if the driver supports the `ArraysIndirect` and
+    /// `ElementsIndirect` draw call kinds.
+    pub fn supports_draw_indirect(&self) -> bool {
+        self.has_draw_indirect
+    }
+
+    /// Returns `true` if the driver supports the `MultiArraysIndirect` and
+    /// `MultiElementsIndirect` draw call kinds.
+    pub fn supports_multi_draw_indirect(&self) -> bool {
+        self.has_multi_draw_indirect
+    }
+
+    /// Returns `true` if the driver supports vertex attributes with a
+    /// non-zero instance divisor.
+    pub fn supports_instanced_arrays(&self) -> bool {
+        self.has_instanced_arrays
+    }
+
+    /// Returns `true` if the driver supports the `I2_10_10_10Rev`,
+    /// `I2_10_10_10RevNorm`, `U2_10_10_10Rev` and `U2_10_10_10RevNorm`
+    /// vertex formats.
+    pub fn supports_vertex_type_2_10_10_10_rev(&self) -> bool {
+        self.has_vertex_type_2_10_10_10_rev
+    }
+
+    /// Returns `true` if the driver supports the `U10F11F11FRev` vertex
+    /// format.
+    pub fn supports_vertex_type_10f_11f_11f_rev(&self) -> bool {
+        self.has_vertex_type_10f_11f_11f_rev
+    }
+
+    /// Returns the required byte alignment of uniform buffer [`Slice`]
+    /// offsets bound by an [`Invocation`].
+    ///
+    /// [`Slice`]: buffer/struct.Slice.html
+    /// [`Invocation`]: program/struct.Invocation.html
+    pub fn uniform_buffer_offset_alignment(&self) -> usize {
+        self.uniform_buffer_offset_alignment
+    }
+
+    /// Returns the number of vertex attribute locations supported by the
+    /// driver.
+    pub fn max_vertex_attributes(&self) -> usize {
+        self.max_vertex_attributes
+    }
+
+    /// Returns the number of uniform block binding points supported by
+    /// the driver.
+    pub fn max_uniform_blocks(&self) -> usize {
+        self.max_uniform_blocks
+    }
+
+    /// Returns the number of sampler binding points supported by the
+    /// driver.
+    pub fn max_samplers(&self) -> usize {
+        self.max_samplers
+    }
+
+    /// Returns the number of framebuffer color attachments supported by
+    /// the driver.
+    pub fn max_color_attachments(&self) -> usize {
+        self.max_color_attachments
+    }
+
+    /// Returns the number of transform feedback varyings that may be
+    /// captured into separate buffers.
+    pub fn max_transform_feedback_separate_attributes(&self) -> usize {
+        self.max_transform_feedback_separate_attributes
     }
 
     /// Create an GPU buffer with `size` bytes of uninitialized memory.
@@ -124,15 +540,15 @@ impl Factory {
         kind: buffer::Kind,
         usage: buffer::Usage,
     ) -> Buffer {
-        let mut buf = self.empty_buffer(kind, usage);
-        self.backend.bind_buffer(buf.id(), kind.as_gl_enum());
+        let buf = self.empty_buffer(kind, usage);
+        self.backend.bind_buffer(buf.id(), TRANSFER_TARGET);
         self.backend.buffer_data(
-            kind.as_gl_enum(),
+            TRANSFER_TARGET,
             size,
             ptr::null() as *const (),
             usage.as_gl_enum(),
         );
-        self.backend.bind_buffer(0, kind.as_gl_enum());
+        self.backend.bind_buffer(0, TRANSFER_TARGET);
         buf.set_size(size);
         buf
     }
@@ -143,18 +559,45 @@ impl Factory {
         kind: buffer::Kind,
         usage: buffer::Usage,
     ) -> Buffer {
+        self.collect_garbage();
         let id = self.backend.gen_buffer();
         let size = 0;
         let tx = self.queues.buffer.tx();
         Buffer::new(id, kind, size, usage, tx)
     }
 
+    /// Create an empty GPU buffer holding elements of type `T`.
+    pub fn empty_typed_buffer<T: Pod>(
+        &self,
+        kind: buffer::Kind,
+        usage: buffer::Usage,
+    ) -> TypedBuffer<T> {
+        TypedBuffer::new(self.empty_buffer(kind, usage))
+    }
+
+    /// Create a heap that sub-allocates from GPU buffers of at least
+    /// `block_size` bytes.
+    pub fn heap(&self, block_size: usize, usage: buffer::Usage) -> Heap {
+        Heap::new(self.clone(), block_size, usage)
+    }
+
     /// A collection of GPU buffers that may be drawn with a material.
+    ///
+    /// Attributes are indexed by location and there may be no more than
+    /// [`max_vertex_attributes`] of them.
+    ///
+    /// [`max_vertex_attributes`]: #method.max_vertex_attributes
     pub fn vertex_array(
         &self,
-        attributes: [Option<vertex_array::Attribute>; vertex_array::MAX_ATTRIBUTES],
+        attributes: &[Option<vertex_array::Attribute>],
         indices: Option<vertex_array::Indices>,
     ) -> VertexArray {
+        assert!(
+            attributes.len() <= self.max_vertex_attributes,
+            "{} vertex attributes exceeds the driver limit of {}",
+            attributes.len(),
+            self.max_vertex_attributes,
+        );
         let id = self.backend.gen_vertex_array();
         let tx = self.queues.vertex_array.tx();
 
@@ -164,24 +607,157 @@ impl Factory {
             if let Some(ref accessor) = indices {
                 self.backend.bind_buffer(accessor.buffer().id(), gl::ELEMENT_ARRAY_BUFFER);
             }
-            for binding in 0 .. vertex_array::MAX_ATTRIBUTES {
-                if let Some(ref accessor) = attributes[binding] {
-                    self.backend.bind_buffer(accessor.buffer().id(), gl::ARRAY_BUFFER);
-                    self.backend.enable_vertex_attrib_array(binding as _);
-                    self.backend.vertex_attrib_pointer(
-                        binding as u8,
-                        accessor.format().size() as _,
-                        accessor.format().gl_data_type(),
-                        accessor.format().norm(),
-                        accessor.stride() as _,
-                        accessor.offset(),
-                    )
+            for (binding, attribute) in attributes.iter().enumerate() {
+                if let Some(ref attribute) = *attribute {
+                    self.bind_vertex_attribute(binding as u8, attribute, 0);
                 }
             }
             self.backend.bind_vertex_array(0);
         }
 
-        VertexArray::new(id, attributes, indices, tx)
+        VertexArray::new(id, attributes.to_vec(), indices, tx)
+    }
+
+    /// Binds a new data source to one attribute of an existing vertex
+    /// array, replacing any previous binding.
+    pub fn set_vertex_attribute(
+        &self,
+        vertex_array: &VertexArray,
+        index: u8,
+        attribute: vertex_array::Attribute,
+    ) {
+        assert!(
+            (index as usize) < self.max_vertex_attributes,
+            "vertex attribute index out of range",
+        );
+        let previous_divisor = vertex_array
+            .replace_attribute(index, Some(attribute.clone()))
+            .map(|attribute| attribute.divisor())
+            .unwrap_or(0);
+        self.backend.bind_vertex_array(vertex_array.id());
+        self.bind_vertex_attribute(index, &attribute, previous_divisor);
+        self.backend.bind_vertex_array(0);
+    }
+
+    /// Specifies one attribute of the currently bound vertex array, which
+    /// previously had the given instance divisor.
+    ///
+    /// Constant attributes only disable the attribute array here since
+    /// their values are context state, applied in `prepare_draw`.
+    fn bind_vertex_attribute(
+        &self,
+        index: u8,
+        attribute: &vertex_array::Attribute,
+        previous_divisor: u32,
+    ) {
+        match *attribute {
+            Attribute::Buffer(ref accessor) => {
+                self.backend.bind_buffer(accessor.buffer().id(), gl::ARRAY_BUFFER);
+                self.backend.enable_vertex_attrib_array(index);
+                self.vertex_attrib_pointer(index, accessor);
+            }
+            Attribute::Constant(_) => {
+                self.backend.disable_vertex_attrib_array(index);
+            }
+        }
+        if attribute.divisor() != previous_divisor {
+            assert!(
+                attribute.divisor() == 0 || self.has_instanced_arrays,
+                "instanced vertex attributes are not supported by the driver",
+            );
+            self.backend.vertex_attrib_divisor(index, attribute.divisor());
+        }
+    }
+
+    /// Removes the data source of one attribute of an existing vertex
+    /// array.
+    pub fn clear_vertex_attribute(&self, vertex_array: &VertexArray, index: u8) {
+        assert!(
+            (index as usize) < self.max_vertex_attributes,
+            "vertex attribute index out of range",
+        );
+        if let Some(previous) = vertex_array.replace_attribute(index, None) {
+            self.backend.bind_vertex_array(vertex_array.id());
+            self.backend.disable_vertex_attrib_array(index);
+            if previous.divisor() != 0 {
+                self.backend.vertex_attrib_divisor(index, 0);
+            }
+            self.backend.bind_vertex_array(0);
+        }
+    }
+
+    /// Replaces the index data of an existing vertex array.
+    ///
+    /// Passing `None` removes the index data, after which only array draw
+    /// calls may be made.
+    pub fn set_vertex_indices(
+        &self,
+        vertex_array: &VertexArray,
+        indices: Option<vertex_array::Indices>,
+    ) {
+        let id = indices.as_ref().map(|accessor| accessor.buffer().id()).unwrap_or(0);
+        self.backend.bind_vertex_array(vertex_array.id());
+        self.backend.bind_buffer(id, gl::ELEMENT_ARRAY_BUFFER);
+        self.backend.bind_vertex_array(0);
+        vertex_array.set_indices(indices);
+    }
+
+    /// Specifies the data source of a vertex attribute of the currently
+    /// bound vertex array.
+    fn vertex_attrib_pointer(&self, index: u8, accessor: &buffer::Accessor) {
+        let format = accessor.format();
+        match format {
+            buffer::Format::I2_10_10_10Rev |
+            buffer::Format::I2_10_10_10RevNorm |
+            buffer::Format::U2_10_10_10Rev |
+            buffer::Format::U2_10_10_10RevNorm => {
+                assert!(
+                    self.has_vertex_type_2_10_10_10_rev,
+                    "2_10_10_10 packed vertex formats are not supported by the driver",
+                );
+            }
+            buffer::Format::U10F11F11FRev => {
+                assert!(
+                    self.has_vertex_type_10f_11f_11f_rev,
+                    "10F_11F_11F packed vertex format is not supported by the driver",
+                );
+            }
+            _ => {}
+        }
+        match format.class() {
+            buffer::format::Class::Float => {
+                self.backend.vertex_attrib_pointer(
+                    index,
+                    format.size() as _,
+                    format.gl_data_type(),
+                    format.norm(),
+                    accessor.stride() as _,
+                    accessor.offset(),
+                );
+            }
+            buffer::format::Class::Integer => {
+                self.backend.vertex_attrib_i_pointer(
+                    index,
+                    format.size() as _,
+                    format.gl_data_type(),
+                    accessor.stride() as _,
+                    accessor.offset(),
+                );
+            }
+            buffer::format::Class::Double => {
+                assert!(
+                    self.has_vertex_attrib_64bit,
+                    "64-bit vertex attributes are not supported by the driver",
+                );
+                self.backend.vertex_attrib_l_pointer(
+                    index,
+                    format.size() as _,
+                    format.gl_data_type(),
+                    accessor.stride() as _,
+                    accessor.offset(),
+                );
+            }
+        }
     }
 
     /// Compile GLSL shader code into a shader object.
@@ -204,14 +780,117 @@ impl Factory {
         fragment: &shader::Object,
         bindings: &program::Bindings,
     ) -> Program {
+        self.link_program(vertex, None, fragment, bindings)
+    }
+
+    /// Link GLSL objects, including a geometry shader, to create a GLSL
+    /// program.
+    ///
+    /// Only programs with a geometry shader may draw the adjacency
+    /// primitive topologies.
+    pub fn geometry_program(
+        &self,
+        vertex: &shader::Object,
+        geometry: &shader::Object,
+        fragment: &shader::Object,
+        bindings: &program::Bindings,
+    ) -> Program {
+        self.link_program(vertex, Some(geometry), fragment, bindings)
+    }
+
+    /// Links a program from its shader stages.
+    fn link_program(
+        &self,
+        vertex: &shader::Object,
+        geometry: Option<&shader::Object>,
+        fragment: &shader::Object,
+        bindings: &program::Bindings,
+    ) -> Program {
+        assert_eq!(vertex.kind(), shader::Kind::Vertex, "expected a vertex shader");
+        assert_eq!(fragment.kind(), shader::Kind::Fragment, "expected a fragment shader");
         let id = self.backend.create_program();
         self.backend.attach_shader(id, vertex.id());
+        if let Some(geometry) = geometry {
+            assert_eq!(geometry.kind(), shader::Kind::Geometry, "expected a geometry shader");
+            self.backend.attach_shader(id, geometry.id());
+        }
         self.backend.attach_shader(id, fragment.id());
+        assert!(
+            bindings.attributes.len() <= self.max_vertex_attributes,
+            "{} attribute bindings exceeds the driver limit of {}",
+            bindings.attributes.len(),
+            self.max_vertex_attributes,
+        );
+        assert!(
+            bindings.fragment_outputs.len() <= self.max_color_attachments,
+            "{} fragment output bindings exceeds the driver limit of {}",
+            bindings.fragment_outputs.len(),
+            self.max_color_attachments,
+        );
+        for (location, attribute) in bindings.attributes.iter().enumerate() {
+            if let AttributeBinding::Named(name) = *attribute {
+                let cstr = util::cstr(name);
+                self.backend.bind_attrib_location(id, location as u32, cstr);
+            }
+        }
+        for (color, output) in bindings.fragment_outputs.iter().enumerate() {
+            if let FragmentOutputBinding::Named(name) = *output {
+                let cstr = util::cstr(name);
+                self.backend.bind_frag_data_location(id, color as u32, cstr);
+            }
+        }
+        match bindings.transform_feedback_varyings {
+            TransformFeedbackVaryings::Interleaved(names) => {
+                let names: Vec<_> = names.iter().map(util::cstr).collect();
+                self.backend.transform_feedback_varyings(
+                    id,
+                    &names,
+                    gl::INTERLEAVED_ATTRIBS,
+                );
+            }
+            TransformFeedbackVaryings::Separate(names) => {
+                assert!(
+                    names.len() <= self.max_transform_feedback_separate_attributes,
+                    "{} separate transform feedback varyings exceeds the driver limit of {}",
+                    names.len(),
+                    self.max_transform_feedback_separate_attributes,
+                );
+                let names: Vec<_> = names.iter().map(util::cstr).collect();
+                self.backend.transform_feedback_varyings(
+                    id,
+                    &names,
+                    gl::SEPARATE_ATTRIBS,
+                );
+            }
+            TransformFeedbackVaryings::None => {}
+        }
         self.backend.link_program(id);
         let tx = self.queues.program.tx();
         let mut program = Program::new(id, tx);
-        for binding in 0 .. MAX_UNIFORM_BLOCKS {
-            match bindings.uniform_blocks[binding] {
+        program.attributes = self.query_active_attributes(&program);
+        program.transform_feedback_varyings = bindings.transform_feedback_varyings;
+        if geometry.is_some() {
+            let output = self.backend.get_programiv(id, gl::GEOMETRY_OUTPUT_TYPE);
+            program.geometry_output = Some(match output as u32 {
+                gl::POINTS => gl::POINTS,
+                gl::LINE_STRIP => gl::LINES,
+                _ => gl::TRIANGLES,
+            });
+        }
+        assert!(
+            bindings.uniform_blocks.len() <= self.max_uniform_blocks,
+            "{} uniform block bindings exceeds the driver limit of {}",
+            bindings.uniform_blocks.len(),
+            self.max_uniform_blocks,
+        );
+        assert!(
+            bindings.samplers.len() <= self.max_samplers,
+            "{} sampler bindings exceeds the driver limit of {}",
+            bindings.samplers.len(),
+            self.max_samplers,
+        );
+        for (binding, uniform_block) in bindings.uniform_blocks.iter().enumerate() {
+            match *uniform_block {
                 UniformBlockBinding::Required(name) => {
                     let cstr = util::cstr(name);
                     let index = self
@@ -238,8 +917,9 @@ impl Factory {
                 UniformBlockBinding::None => {}
             }
         }
-        for binding in 0 .. MAX_SAMPLERS {
-            match bindings.samplers[binding] {
+        program.samplers = vec![None; bindings.samplers.len()];
+        for (binding, sampler) in bindings.samplers.iter().enumerate() {
+            match *sampler {
                 SamplerBinding::Required(name) => {
                     let cstr = util::cstr(name);
                     let index = self
@@ -261,6 +941,121 @@ impl Factory {
         program
     }
 
+    /// Retrieves the active vertex shader inputs of a linked program.
+    fn query_active_attributes(&self, program: &Program) -> Vec<ActiveAttribute> {
+        let count = self.backend.get_programiv(program.id(), gl::ACTIVE_ATTRIBUTES);
+        let mut attributes = Vec::new();
+        for index in 0 .. count as u32 {
+            let (name, size, ty) = self.backend.get_active_attrib(program.id(), index);
+            let location = match ffi::CString::new(name.clone()) {
+                Ok(cstr) => self.backend.get_attrib_location(program.id(), &cstr),
+                Err(_) => -1,
+            };
+            if location < 0 {
+                // Built-in inputs such as `gl_VertexID` have no location.
+                continue;
+            }
+            let (class, columns, components) = match ty {
+                gl::FLOAT => (Class::Float, 1, 1),
+                gl::FLOAT_VEC2 => (Class::Float, 1, 2),
+                gl::FLOAT_VEC3 => (Class::Float, 1, 3),
+                gl::FLOAT_VEC4 => (Class::Float, 1, 4),
+                gl::FLOAT_MAT2 => (Class::Float, 2, 2),
+                gl::FLOAT_MAT2x3 => (Class::Float, 2, 3),
+                gl::FLOAT_MAT2x4 => (Class::Float, 2, 4),
+                gl::FLOAT_MAT3x2 => (Class::Float, 3, 2),
+                gl::FLOAT_MAT3 => (Class::Float, 3, 3),
+                gl::FLOAT_MAT3x4 => (Class::Float, 3, 4),
+                gl::FLOAT_MAT4x2 => (Class::Float, 4, 2),
+                gl::FLOAT_MAT4x3 => (Class::Float, 4, 3),
+                gl::FLOAT_MAT4 => (Class::Float, 4, 4),
+                gl::INT | gl::UNSIGNED_INT => (Class::Integer, 1, 1),
+                gl::INT_VEC2 | gl::UNSIGNED_INT_VEC2 => (Class::Integer, 1, 2),
+                gl::INT_VEC3 | gl::UNSIGNED_INT_VEC3 => (Class::Integer, 1, 3),
+                gl::INT_VEC4 | gl::UNSIGNED_INT_VEC4 => (Class::Integer, 1, 4),
+                gl::DOUBLE => (Class::Double, 1, 1),
+                gl::DOUBLE_VEC2 => (Class::Double, 1, 2),
+                gl::DOUBLE_VEC3 => (Class::Double, 1, 3),
+                gl::DOUBLE_VEC4 => (Class::Double, 1, 4),
+                gl::DOUBLE_MAT2 => (Class::Double, 2, 2),
+                gl::DOUBLE_MAT2x3 => (Class::Double, 2, 3),
+                gl::DOUBLE_MAT2x4 => (Class::Double, 2, 4),
+                gl::DOUBLE_MAT3x2 => (Class::Double, 3, 2),
+                gl::DOUBLE_MAT3 => (Class::Double, 3, 3),
+                gl::DOUBLE_MAT3x4 => (Class::Double, 3, 4),
+                gl::DOUBLE_MAT4x2 => (Class::Double, 4, 2),
+                gl::DOUBLE_MAT4x3 => (Class::Double, 4, 3),
+                gl::DOUBLE_MAT4 => (Class::Double, 4, 4),
+                _ => panic!("unrecognized type {:#x} of vertex attribute `{}`", ty, name),
+            };
+            attributes.push(ActiveAttribute {
+                name,
+                location: location as u32,
+                columns: columns * size as u32,
+                components,
+                class,
+            });
+        }
+        attributes
+    }
+
+    /// Checks that a vertex array supplies every active input of a program
+    /// with a matching class and component count, panicking with a
+    /// description of the first mismatch.
+    ///
+    /// A buffer attribute may supply fewer components than the input reads,
+    /// in which case missing y and z components are 0 and w is 1. This
+    /// check runs before every draw in debug builds.
+    pub fn validate_vertex_array(&self, program: &Program, vertex_array: &VertexArray) {
+        vertex_array.with_attributes(|attributes| {
+            for input in program.attributes() {
+                for location in input.locations() {
+                    let attribute = attributes
+                        .get(location as usize)
+                        .and_then(Option::as_ref);
+                    let (class, components) = match attribute {
+                        Some(&Attribute::Buffer(ref accessor)) => {
+                            let format = accessor.format();
+                            (format.class(), format.size())
+                        }
+                        // Constants supply all four components.
+                        Some(&Attribute::Constant(Constant::F32(_))) => {
+                            (Class::Float, input.components)
+                        }
+                        Some(&Attribute::Constant(Constant::I32(_))) |
+                        Some(&Attribute::Constant(Constant::U32(_))) => {
+                            (Class::Integer, input.components)
+                        }
+                        None => {
+                            panic!(
+                                "vertex attribute {} (`{}`) is not supplied by vertex array {}",
+                                location,
+                                input.name,
+                                vertex_array.id(),
+                            );
+                        }
+                    };
+                    assert!(
+                        class == input.class,
+                        "vertex attribute {} (`{}`) is read as {:?} but supplied as {:?}",
+                        location,
+                        input.name,
+                        input.class,
+                        class,
+                    );
+                    assert!(
+                        components <= input.components,
+                        "vertex attribute {} (`{}`) has {} components but {} are supplied",
+                        location,
+                        input.name,
+                        input.components,
+                        components,
+                    );
+                }
+            }
+        });
+    }
+
     /// Sets the binding index for a named uniform block.
     pub fn set_uniform_block_binding(
         &self,
@@ -323,6 +1118,24 @@ impl Factory {
         Texture2::new(id, width, height, mipmap, format, tx)
     }
 
+    /// Create a buffer texture whose texels are sourced from `buffer`.
+    pub fn texture_buffer(
+        &self,
+        buffer: &Buffer,
+        format: TexelFormat,
+    ) -> TextureBuffer {
+        let id = self.backend.gen_texture();
+        let tx = self.queues.texture.tx();
+        self.backend.bind_texture(gl::TEXTURE_BUFFER, id);
+        self.backend.tex_buffer(
+            gl::TEXTURE_BUFFER,
+            format.as_gl_enum(),
+            buffer.id(),
+        );
+        self.backend.bind_texture(gl::TEXTURE_BUFFER, 0);
+        TextureBuffer::new(id, buffer.clone(), format, tx)
+    }
+
     /// Read back the contents of a [`Texture2`].
     ///
     /// [`Texture2`]: texture/struct.Texture2.html
@@ -344,6 +1157,98 @@ impl Factory {
         );
     }
 
+    /// Begin reading back the contents of a [`Texture2`] without stalling
+    /// the pipeline.
+    ///
+    /// The contents are written into a pixel pack buffer by the GPU. Use
+    /// [`poll_readback`] to check for completion and [`finish_readback`]
+    /// to retrieve the data. Rows are padded to a multiple of 4 bytes.
+    ///
+    /// A new pixel pack buffer is allocated for every call; use
+    /// [`readback_texture2_into`] to reuse one instead.
+    ///
+    /// [`Texture2`]: texture/struct.Texture2.html
+    /// [`poll_readback`]: #method.poll_readback
+    /// [`finish_readback`]: #method.finish_readback
+    /// [`readback_texture2_into`]: #method.readback_texture2_into
+    pub fn readback_texture2<F>(&self, texture: &Texture2, format: F) -> Readback
+        where image::Format: From<F>
+    {
+        let buffer = self.empty_buffer(
+            buffer::Kind::PixelPack,
+            buffer::Usage::StreamRead,
+        );
+        self.readback_texture2_into(texture, format, buffer)
+    }
+
+    /// Begin reading back the contents of a [`Texture2`] into an existing
+    /// pixel pack buffer, which is grown if it is too small.
+    ///
+    /// The buffer may be recovered with [`Readback::into_buffer`] once the
+    /// data has been retrieved and reused for the next readback.
+    ///
+    /// [`Texture2`]: texture/struct.Texture2.html
+    /// [`Readback::into_buffer`]: texture/struct.Readback.html#method.into_buffer
+    pub fn readback_texture2_into<F>(
+        &self,
+        texture: &Texture2,
+        format: F,
+        buffer: Buffer,
+    ) -> Readback
+        where image::Format: From<F>
+    {
+        assert_unmapped(&buffer);
+        let format = image::Format::from(format);
+        let size = format.image_size(texture.width(), texture.height());
+        if buffer.size() < size {
+            self.backend.bind_buffer(buffer.id(), TRANSFER_TARGET);
+            self.backend.buffer_data(
+                TRANSFER_TARGET,
+                size,
+                ptr::null() as *const (),
+                buffer.usage().as_gl_enum(),
+            );
+            self.backend.bind_buffer(0, TRANSFER_TARGET);
+            buffer.set_size(size);
+        }
+        self.backend.bind_buffer(buffer.id(), gl::PIXEL_PACK_BUFFER);
+        self.backend.bind_texture(gl::TEXTURE_2D, texture.id());
+        let (type_, format) = format.as_gl_enums();
+        self.backend.get_tex_image(
+            gl::TEXTURE_2D,
+            format,
+            type_,
+            ptr::null_mut(),
+        );
+        self.backend.bind_texture(gl::TEXTURE_2D, 0);
+        self.backend.bind_buffer(0, gl::PIXEL_PACK_BUFFER);
+        Readback::new(buffer, size, self.fence())
+    }
+
+    /// Returns `true` if the GPU has finished writing the contents of a
+    /// [`Readback`], i.e. [`finish_readback`] will not block.
+    ///
+    /// [`Readback`]: texture/struct.Readback.html
+    /// [`finish_readback`]: #method.finish_readback
+    pub fn poll_readback(&self, readback: &Readback) -> bool {
+        self.poll_fence(readback.fence())
+    }
+
+    /// Retrieve the contents of a [`Readback`], blocking if the GPU has not
+    /// yet finished writing them.
+    ///
+    /// [`Readback`]: texture/struct.Readback.html
+    pub fn finish_readback(&self, readback: &Readback) -> Vec<u8> {
+        self.wait_fence(readback.fence());
+        let mapping = self.map_buffer(
+            buffer::Slice::new(readback.buffer(), 0, readback.size()),
+            buffer::Access::Read,
+        );
+        let data = mapping.as_bytes().to_vec();
+        self.unmap_buffer(mapping);
+        data
+    }
+
     /// (Re)-initialize the contents of a [`Texture2`].
     ///
     /// [`Texture2`]: texture/struct.Texture2.html
@@ -372,6 +1277,84 @@ impl Factory {
         self.backend.bind_texture(gl::TEXTURE_2D, 0);
     }
 
+    /// (Re)-initialize the contents of a [`Texture2`] from pixel data
+    /// previously written into a buffer, e.g. through a [`Mapping`].
+    ///
+    /// The transfer is performed by the GPU, so the call returns without
+    /// waiting for the pixel data to be consumed. Rows are expected to be
+    /// padded to a multiple of 4 bytes.
+    ///
+    /// [`Texture2`]: texture/struct.Texture2.html
+    /// [`Mapping`]: buffer/struct.Mapping.html
+    pub fn write_texture2_from_buffer<F>(
+        &self,
+        texture: &Texture2,
+        format: F,
+        slice: buffer::Slice,
+    )
+        where image::Format: From<F>
+    {
+        assert_unmapped(slice.buffer());
+        let format = image::Format::from(format);
+        assert!(
+            slice.offset() + slice.length() <= slice.buffer().size(),
+            "pixel unpack range out of bounds",
+        );
+        assert!(
+            slice.length() >= format.image_size(texture.width(), texture.height()),
+            "pixel unpack range too small for texture",
+        );
+        self.backend.bind_buffer(slice.id(), gl::PIXEL_UNPACK_BUFFER);
+        self.backend.bind_texture(gl::TEXTURE_2D, texture.id());
+        let (type_, format) = format.as_gl_enums();
+        self.backend.tex_image_2d(
+            gl::TEXTURE_2D,
+            texture.format().as_gl_enum(),
+            texture.width() as u32,
+            texture.height() as u32,
+            format,
+            type_,
+            slice.offset() as *const _,
+        );
+        if texture.mipmap() {
+            self.backend.generate_mipmap(gl::TEXTURE_2D);
+        }
+        self.backend.bind_texture(gl::TEXTURE_2D, 0);
+        self.backend.bind_buffer(0, gl::PIXEL_UNPACK_BUFFER);
+    }
+
+    /// Insert a fence into the GPU command stream.
+    pub fn fence(&self) -> Fence {
+        self.collect_garbage();
+        let id = self.backend.fence_sync();
+        let tx = self.queues.fence.tx();
+        Fence::new(id, tx)
+    }
+
+    /// Returns `true` if the GPU has passed the given fence.
+    pub fn poll_fence(&self, fence: &Fence) -> bool {
+        self.client_wait_fence(fence, 0)
+    }
+
+    /// Block until the GPU has passed the given fence.
+    pub fn wait_fence(&self, fence: &Fence) {
+        while !self.client_wait_fence(fence, FENCE_WAIT_TIMEOUT) {}
+    }
+
+    /// Wait up to `timeout` nanoseconds for the GPU to pass the given fence.
+    fn client_wait_fence(&self, fence: &Fence, timeout: u64) -> bool {
+        let result = self.backend.client_wait_sync(
+            fence.id(),
+            gl::SYNC_FLUSH_COMMANDS_BIT,
+            timeout,
+        );
+        match result {
+            gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => true,
+            gl::TIMEOUT_EXPIRED => false,
+            _ => panic!("failed to wait for fence"),
+        }
+    }
+
     /// Create a renderbuffer.
     pub fn renderbuffer(
         &self,
@@ -405,15 +1388,21 @@ impl Factory {
         &self,
         width: u32,
         height: u32,
-        color_attachments: [ColorAttachment; MAX_COLOR_ATTACHMENTS],
+        color_attachments: &[ColorAttachment],
         depth_stencil_attachment: DepthStencilAttachment,
     ) -> Framebuffer {
+        assert!(
+            color_attachments.len() <= self.max_color_attachments,
+            "{} color attachments exceeds the driver limit of {}",
+            color_attachments.len(),
+            self.max_color_attachments,
+        );
         let id = self.backend.gen_framebuffer();
         let tx = self.queues.framebuffer.tx();
         self.backend.bind_framebuffer(id);
         let mut draw_buffers = vec![];
-        for attachment in 0 .. MAX_COLOR_ATTACHMENTS {
-            match color_attachments[attachment] {
+        for (attachment, color_attachment) in color_attachments.iter().enumerate() {
+            match *color_attachment {
                 ColorAttachment::Renderbuffer(ref renderbuffer) => {
                     draw_buffers.push(gl::COLOR_ATTACHMENT0 + attachment as u32);
                     self.backend.framebuffer_renderbuffer(
@@ -449,7 +1438,7 @@ impl Factory {
             tx,
             width,
             height,
-            color_attachments,
+            color_attachments.to_vec(),
             depth_stencil_attachment,
         )
     }
@@ -472,6 +1461,189 @@ impl Factory {
         draw_call: &DrawCall,
         invocation: &Invocation,
     ) {
+        self.prepare_draw(framebuffer, state, vertex_array, invocation);
+        self.check_primitive(invocation.program, draw_call.primitive);
+        self.dispatch_draw(vertex_array, draw_call);
+        self.finish_draw();
+    }
+
+    /// Perform many draw calls sharing the same state in one submission.
+    pub fn multi_draw(
+        &self,
+        framebuffer: &Framebuffer,
+        state: &State,
+        vertex_array: &VertexArray,
+        draw_call: &MultiDrawCall,
+        invocation: &Invocation,
+    ) {
+        if draw_call.ranges.is_empty() {
+            return;
+        }
+        self.prepare_draw(framebuffer, state, vertex_array, invocation);
+        self.check_primitive(invocation.program, draw_call.primitive);
+        let mode = draw_call.primitive.as_gl_enum();
+        let counts: Vec<i32> = draw_call.ranges
+            .iter()
+            .map(|range| range.count as i32)
+            .collect();
+        match draw_call.kind {
+            MultiKind::Arrays => {
+                let offsets: Vec<i32> = draw_call.ranges
+                    .iter()
+                    .map(|range| range.offset as i32)
+                    .collect();
+                self.backend.multi_draw_arrays(mode, &offsets, &counts);
+            }
+            MultiKind::Elements => {
+                let format = self.index_type(vertex_array);
+                let offsets: Vec<usize> = draw_call.ranges
+                    .iter()
+                    .map(|range| range.offset)
+                    .collect();
+                self.backend.multi_draw_elements(mode, &offsets, &counts, format);
+            }
+            MultiKind::ElementsBaseVertex => {
+                let format = self.index_type(vertex_array);
+                let offsets: Vec<usize> = draw_call.ranges
+                    .iter()
+                    .map(|range| range.offset)
+                    .collect();
+                let base_vertices: Vec<i32> = draw_call.ranges
+                    .iter()
+                    .map(|range| range.base_vertex)
+                    .collect();
+                self.backend.multi_draw_elements_base_vertex(
+                    mode,
+                    &offsets,
+                    &counts,
+                    format,
+                    &base_vertices,
+                );
+            }
+        }
+        self.finish_draw();
+    }
+
+    /// Perform a draw call, capturing the vertex shader outputs declared in
+    /// the program [`Bindings`] into the given buffer slices.
+    ///
+    /// With interleaved capture only the first slice is used, otherwise
+    /// each captured varying is written to the slice at its index. Panics
+    /// if the slices do not match the varyings the program was linked with.
+    ///
+    /// [`Bindings`]: program/struct.Bindings.html
+    pub fn draw_transform_feedback(
+        &self,
+        framebuffer: &Framebuffer,
+        state: &State,
+        vertex_array: &VertexArray,
+        draw_call: &DrawCall,
+        invocation: &Invocation,
+        capture: &TransformFeedback,
+    ) {
+        match invocation.program.transform_feedback_varyings {
+            TransformFeedbackVaryings::Interleaved(_) => {
+                assert!(
+                    !capture.outputs.is_empty(),
+                    "interleaved transform feedback requires an output slice",
+                );
+            }
+            TransformFeedbackVaryings::Separate(names) => {
+                assert_eq!(
+                    capture.outputs.len(),
+                    names.len(),
+                    "separate transform feedback requires one output slice per varying",
+                );
+            }
+            TransformFeedbackVaryings::None => {
+                panic!("program was linked without transform feedback varyings");
+            }
+        }
+        self.prepare_draw(framebuffer, state, vertex_array, invocation);
+        for (idx, slice) in capture.outputs.iter().enumerate() {
+            assert_unmapped(slice.buffer());
+            assert!(
+                slice.offset() % 4 == 0,
+                "transform feedback buffer offset must be a multiple of 4",
+            );
+            self.bind_buffer_slice(
+                gl::TRANSFORM_FEEDBACK_BUFFER,
+                idx as u32,
+                *slice,
+            );
+        }
+        if capture.rasterizer_discard {
+            self.backend.enable(gl::RASTERIZER_DISCARD);
+        }
+        self.check_primitive(invocation.program, draw_call.primitive);
+        let mode = invocation.program.geometry_output.unwrap_or_else(|| {
+            draw_call.primitive.transform_feedback_mode()
+        });
+        self.backend.begin_transform_feedback(mode);
+        self.dispatch_draw(vertex_array, draw_call);
+        self.backend.end_transform_feedback();
+        if capture.rasterizer_discard {
+            self.backend.disable(gl::RASTERIZER_DISCARD);
+        }
+        for idx in 0 .. capture.outputs.len() {
+            self.backend.bind_buffer_base(
+                gl::TRANSFORM_FEEDBACK_BUFFER,
+                idx as u32,
+                0,
+            );
+        }
+        self.finish_draw();
+    }
+
+    /// Checks that a program can draw the given primitive topology.
+    fn check_primitive(&self, program: &Program, primitive: Primitive) {
+        assert!(
+            !primitive.is_adjacency() || program.has_geometry_shader(),
+            "{:?} primitives require a program with a geometry shader",
+            primitive,
+        );
+    }
+
+    /// Binds a buffer slice to an indexed binding point.
+    fn bind_buffer_slice(&self, target: u32, index: u32, slice: buffer::Slice) {
+        assert!(
+            slice.offset() + slice.length() <= slice.buffer().size(),
+            "buffer range out of bounds",
+        );
+        if slice.offset() == 0 && slice.length() == slice.buffer().size() {
+            self.backend.bind_buffer_base(target, index, slice.id());
+        } else {
+            self.backend.bind_buffer_range(
+                target,
+                index,
+                slice.id(),
+                slice.offset(),
+                slice.length(),
+            );
+        }
+    }
+
+    /// Binds the framebuffer, pipeline state, vertex array, and program
+    /// resources ahead of a draw call.
+    fn prepare_draw(
+        &self,
+        framebuffer: &Framebuffer,
+        state: &State,
+        vertex_array: &VertexArray,
+        invocation: &Invocation,
+    ) {
+        self.collect_garbage();
+        if let Some(indices) = vertex_array.indices() {
+            assert_unmapped(indices.buffer());
+        }
+        for slice in invocation.uniforms.iter().filter_map(|opt| *opt) {
+            assert_unmapped(slice.buffer());
+        }
+        for source in invocation.samplers.iter().filter_map(|opt| *opt) {
+            if let SamplerSource::Buffer(texture) = source {
+                assert_unmapped(texture.buffer());
+            }
+        }
         self.backend.bind_framebuffer(framebuffer.id());
         match state.viewport {
             Viewport::Max => {
@@ -490,24 +1662,128 @@ impl Factory {
         } else {
             self.backend.disable(gl::CULL_FACE);
         }
-        self.backend.enable(gl::DEPTH_TEST);
-        self.backend.depth_func(state.depth_test.as_gl_enum());
+        if let Some(func) = state.depth_test.as_gl_enum_if_enabled() {
+            self.backend.enable(gl::DEPTH_TEST);
+            self.backend.depth_func(func);
+            self.backend.depth_mask(state.depth_write);
+        } else {
+            self.backend.disable(gl::DEPTH_TEST);
+        }
+        self.backend.depth_range(state.depth_range.near, state.depth_range.far);
+        match state.blending {
+            Blending::None => {
+                self.backend.disable(gl::BLEND);
+            }
+            Blending::All(blend) => {
+                self.backend.enable(gl::BLEND);
+                self.backend.blend_equation_separate(
+                    blend.rgb.equation.as_gl_enum(),
+                    blend.alpha.equation.as_gl_enum(),
+                );
+                self.backend.blend_func_separate(
+                    blend.rgb.source.as_gl_enum(),
+                    blend.rgb.destination.as_gl_enum(),
+                    blend.alpha.source.as_gl_enum(),
+                    blend.alpha.destination.as_gl_enum(),
+                );
+            }
+            Blending::PerAttachment(ref blends) => {
+                assert!(
+                    self.has_draw_buffers_blend,
+                    "per-attachment blending is not supported by the driver",
+                );
+                assert!(
+                    blends.len() <= self.max_color_attachments,
+                    "{} attachment blend states exceeds the driver limit of {}",
+                    blends.len(),
+                    self.max_color_attachments,
+                );
+                for index in 0 .. self.max_color_attachments {
+                    let buffer = index as u32;
+                    match blends.get(index).and_then(|blend| *blend) {
+                        Some(blend) => {
+                            self.backend.enablei(gl::BLEND, buffer);
+                            self.backend.blend_equation_separate_i(
+                                buffer,
+                                blend.rgb.equation.as_gl_enum(),
+                                blend.alpha.equation.as_gl_enum(),
+                            );
+                            self.backend.blend_func_separate_i(
+                                buffer,
+                                blend.rgb.source.as_gl_enum(),
+                                blend.rgb.destination.as_gl_enum(),
+                                blend.alpha.source.as_gl_enum(),
+                                blend.alpha.destination.as_gl_enum(),
+                            );
+                        }
+                        None => {
+                            self.backend.disablei(gl::BLEND, buffer);
+                        }
+                    }
+                }
+            }
+        }
+        let color = state.blend_color;
+        self.backend.blend_color(color.r, color.g, color.b, color.a);
+        match state.primitive_restart {
+            PrimitiveRestart::Disabled => {
+                self.backend.disable(gl::PRIMITIVE_RESTART);
+            }
+            PrimitiveRestart::Index(index) => {
+                self.backend.enable(gl::PRIMITIVE_RESTART);
+                self.backend.primitive_restart_index(index);
+            }
+        }
+        if cfg!(debug_assertions) {
+            self.validate_vertex_array(invocation.program, vertex_array);
+        }
         self.backend.bind_vertex_array(vertex_array.id());
+        vertex_array.with_attributes(|attributes| {
+            for (index, attribute) in attributes.iter().enumerate() {
+                match *attribute {
+                    Some(Attribute::Buffer(ref accessor)) => {
+                        assert_unmapped(accessor.buffer());
+                    }
+                    Some(Attribute::Constant(Constant::F32(value))) => {
+                        self.backend.vertex_attrib_4f(index as u8, value);
+                    }
+                    Some(Attribute::Constant(Constant::I32(value))) => {
+                        self.backend.vertex_attrib_i_4i(index as u8, value);
+                    }
+                    Some(Attribute::Constant(Constant::U32(value))) => {
+                        self.backend.vertex_attrib_i_4ui(index as u8, value);
+                    }
+                    None => {}
+                }
+            }
+        });
         self.backend.use_program(invocation.program.id());
+        assert!(
+            invocation.uniforms.len() <= self.max_uniform_blocks,
+            "{} uniform blocks exceeds the driver limit of {}",
+            invocation.uniforms.len(),
+            self.max_uniform_blocks,
+        );
         for (idx, opt) in invocation.uniforms.iter().enumerate() {
-            opt.map(|buf| {
-                self.backend.bind_buffer_base(
-                    gl::UNIFORM_BUFFER,
-                    idx as u32,
-                    buf.id(),
+            if let Some(slice) = *opt {
+                assert!(
+                    slice.offset() % self.uniform_buffer_offset_alignment == 0,
+                    "uniform buffer offset must be a multiple of {}",
+                    self.uniform_buffer_offset_alignment,
                 );
-            });
+                self.bind_buffer_slice(gl::UNIFORM_BUFFER, idx as u32, slice);
+            }
         }
         for (idx, opt) in invocation.samplers.iter().enumerate() {
-            opt.map(|(texture, sampler)| {
-                if let Some(location) = invocation.program.samplers[idx] {
+            let location = invocation.program.samplers.get(idx).cloned();
+            let (source, location) = match (*opt, location) {
+                (Some(source), Some(Some(location))) => (source, location),
+                _ => continue,
+            };
+            self.backend.active_texture(idx as u32);
+            match source {
+                SamplerSource::Texture2(texture, sampler) => {
                     let (id, ty) = (texture.id(), gl::TEXTURE_2D);
-                    self.backend.active_texture(idx as u32);
                     self.backend.bind_texture(ty, id);
                     self.backend.tex_parameteri(
                         ty,
@@ -529,9 +1805,12 @@ impl Factory {
                         gl::TEXTURE_WRAP_T,
                         sampler.wrap_t.as_gl_enum(),
                     );
-                    self.backend.uniform_1i(location as i32, idx as i32);
                 }
-            });
+                SamplerSource::Buffer(texture) => {
+                    self.backend.bind_texture(gl::TEXTURE_BUFFER, texture.id());
+                }
+            }
+            self.backend.uniform_1i(location as i32, idx as i32);
         }
         self.backend.polygon_mode(gl::FRONT_AND_BACK, state.polygon_mode.as_gl_enum());
         match state.polygon_mode {
@@ -539,25 +1818,201 @@ impl Factory {
             PolygonMode::Line(width) => self.backend.line_width(width as f32),
             PolygonMode::Fill => {},
         }
+    }
+
+    /// Issues the draw call proper.
+    fn dispatch_draw(&self, vertex_array: &VertexArray, draw_call: &DrawCall) {
+        let mode = draw_call.primitive.as_gl_enum();
         match draw_call.kind {
             Kind::Arrays => {
-                let mode = draw_call.primitive.as_gl_enum();
                 self.backend.draw_arrays(mode, draw_call.offset, draw_call.count);
             },
-            Kind::ArraysInstanced(_) => {
-                unimplemented!()
+            Kind::ArraysInstanced(instances) => {
+                self.backend.draw_arrays_instanced(
+                    mode,
+                    draw_call.offset,
+                    draw_call.count,
+                    instances,
+                );
+            },
+            Kind::ArraysInstancedBaseInstance { instances, base_instance } => {
+                assert!(
+                    self.has_base_instance,
+                    "base instance draw calls are not supported by the driver",
+                );
+                self.backend.draw_arrays_instanced_base_instance(
+                    mode,
+                    draw_call.offset,
+                    draw_call.count,
+                    instances,
+                    base_instance,
+                );
             },
             Kind::Elements => {
-                let mode = draw_call.primitive.as_gl_enum();
-                let accessor = vertex_array.indices().unwrap();
-                let format = accessor.format().gl_data_type();
+                let format = self.index_type(vertex_array);
                 self.backend.draw_elements(mode, draw_call.offset, draw_call.count, format);
             },
-            Kind::ElementsInstanced(_) => {
-                unimplemented!()
+            Kind::ElementsBaseVertex { base_vertex } => {
+                let format = self.index_type(vertex_array);
+                self.backend.draw_elements_base_vertex(
+                    mode,
+                    draw_call.offset,
+                    draw_call.count,
+                    format,
+                    base_vertex,
+                );
+            },
+            Kind::ElementsRange { start, end } => {
+                let format = self.index_type(vertex_array);
+                self.backend.draw_range_elements(
+                    mode,
+                    start,
+                    end,
+                    draw_call.offset,
+                    draw_call.count,
+                    format,
+                );
+            },
+            Kind::ElementsRangeBaseVertex { start, end, base_vertex } => {
+                let format = self.index_type(vertex_array);
+                self.backend.draw_range_elements_base_vertex(
+                    mode,
+                    start,
+                    end,
+                    draw_call.offset,
+                    draw_call.count,
+                    format,
+                    base_vertex,
+                );
+            },
+            Kind::ElementsInstanced(instances) => {
+                let format = self.index_type(vertex_array);
+                self.backend.draw_elements_instanced(
+                    mode,
+                    draw_call.offset,
+                    draw_call.count,
+                    format,
+                    instances,
+                );
+            },
+            Kind::ElementsInstancedBaseVertex { instances, base_vertex } => {
+                let format = self.index_type(vertex_array);
+                self.backend.draw_elements_instanced_base_vertex(
+                    mode,
+                    draw_call.offset,
+                    draw_call.count,
+                    format,
+                    instances,
+                    base_vertex,
+                );
+            },
+            Kind::ElementsInstancedBaseInstance { instances, base_instance } => {
+                assert!(
+                    self.has_base_instance,
+                    "base instance draw calls are not supported by the driver",
+                );
+                let format = self.index_type(vertex_array);
+                self.backend.draw_elements_instanced_base_instance(
+                    mode,
+                    draw_call.offset,
+                    draw_call.count,
+                    format,
+                    instances,
+                    base_instance,
+                );
+            },
+            Kind::ArraysIndirect(command) => {
+                assert!(
+                    self.has_draw_indirect,
+                    "indirect draw calls are not supported by the driver",
+                );
+                let size = mem::size_of::<ArraysIndirectCommand>();
+                self.bind_indirect_commands(command, size);
+                self.backend.draw_arrays_indirect(mode, command.offset());
+                self.backend.bind_buffer(0, gl::DRAW_INDIRECT_BUFFER);
+            },
+            Kind::ElementsIndirect(command) => {
+                assert!(
+                    self.has_draw_indirect,
+                    "indirect draw calls are not supported by the driver",
+                );
+                let format = self.index_type(vertex_array);
+                let size = mem::size_of::<ElementsIndirectCommand>();
+                self.bind_indirect_commands(command, size);
+                self.backend.draw_elements_indirect(mode, format, command.offset());
+                self.backend.bind_buffer(0, gl::DRAW_INDIRECT_BUFFER);
+            },
+            Kind::MultiArraysIndirect { commands, stride } => {
+                assert!(
+                    self.has_multi_draw_indirect,
+                    "multi indirect draw calls are not supported by the driver",
+                );
+                let size = mem::size_of::<ArraysIndirectCommand>();
+                let count = self.bind_indirect_commands(commands, cmp::max(size, stride));
+                self.backend.multi_draw_arrays_indirect(
+                    mode,
+                    commands.offset(),
+                    count,
+                    stride,
+                );
+                self.backend.bind_buffer(0, gl::DRAW_INDIRECT_BUFFER);
+            },
+            Kind::MultiElementsIndirect { commands, stride } => {
+                assert!(
+                    self.has_multi_draw_indirect,
+                    "multi indirect draw calls are not supported by the driver",
+                );
+                let format = self.index_type(vertex_array);
+                let size = mem::size_of::<ElementsIndirectCommand>();
+                let count = self.bind_indirect_commands(commands, cmp::max(size, stride));
+                self.backend.multi_draw_elements_indirect(
+                    mode,
+                    format,
+                    commands.offset(),
+                    count,
+                    stride,
+                );
+                self.backend.bind_buffer(0, gl::DRAW_INDIRECT_BUFFER);
             },
         }
+    }
+
+    /// Binds the buffer of a slice of indirect draw commands, each `stride`
+    /// bytes apart, returning the number of commands in the slice.
+    fn bind_indirect_commands(&self, commands: buffer::Slice, stride: usize) -> usize {
+        assert_unmapped(commands.buffer());
+        assert!(
+            commands.offset() % 4 == 0,
+            "indirect command offset must be a multiple of 4",
+        );
+        assert!(
+            commands.offset() + commands.length() <= commands.buffer().size(),
+            "indirect command range out of bounds",
+        );
+        let count = commands.length() / stride;
+        assert!(count > 0, "indirect command range too small");
+        self.backend.bind_buffer(commands.id(), gl::DRAW_INDIRECT_BUFFER);
+        count
+    }
+
+    /// Returns the index data type of a vertex array.
+    fn index_type(&self, vertex_array: &VertexArray) -> u32 {
+        vertex_array
+            .indices()
+            .expect("indexed draw call requires vertex array indices")
+            .format()
+            .gl_data_type()
+    }
+
+    /// Unbinds the program and vertex array after a draw call.
+    fn finish_draw(&self) {
         self.backend.use_program(0);
         self.backend.bind_vertex_array(0);
     }
 }
+
+/// Panics if a buffer is mapped into client memory, since using it on the
+/// GPU is an error and may invalidate the mapping.
+fn assert_unmapped(buffer: &Buffer) {
+    assert!(!buffer.is_mapped(), "buffer is mapped into client memory");
+}
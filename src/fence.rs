@@ -0,0 +1,68 @@
+//! GPU synchronization objects.
+
+use queue;
+use std::{cmp, fmt, hash, ops, sync};
+
+/// OpenGL sync object handle, stored as an integer so it may be sent
+/// between threads.
+pub(crate) type Id = usize;
+
+/// Returns the sync object back to the factory upon destruction.
+struct Destructor {
+    id: Id,
+    tx: queue::Sender<Id>,
+}
+
+impl ops::Drop for Destructor {
+    fn drop(&mut self) {
+        let _ = self.tx.send(self.id);
+    }
+}
+
+/// Marks a point in the GPU command stream that may be waited upon.
+#[derive(Clone)]
+pub struct Fence {
+    /// The OpenGL sync object.
+    id: Id,
+
+    /// Returns the sync object back to the factory upon destruction.
+    _destructor: sync::Arc<Destructor>,
+}
+
+impl Fence {
+    /// Constructor.
+    pub(crate) fn new(id: Id, tx: queue::Sender<Id>) -> Self {
+        Self {
+            id,
+            _destructor: sync::Arc::new(Destructor { id, tx }),
+        }
+    }
+
+    /// Returns the OpenGL sync object.
+    pub(crate) fn id(&self) -> Id {
+        self.id
+    }
+}
+
+impl cmp::Eq for Fence {}
+
+impl cmp::PartialEq<Self> for Fence {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl fmt::Debug for Fence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[derive(Debug)]
+        struct Fence(usize);
+
+        Fence(self.id).fmt(f)
+    }
+}
+
+impl hash::Hash for Fence {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
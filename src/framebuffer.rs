@@ -7,8 +7,6 @@ use renderbuffer::Renderbuffer;
 use texture::Texture2;
 use Context;
 
-pub const MAX_COLOR_ATTACHMENTS: usize = 3;
-
 pub(crate) type Id = u32;
 
 /// Framebuffer color attachment.
@@ -66,7 +64,7 @@ impl ops::Drop for Destructor {
 enum Attachments {
     External,
     Internal {
-        color: [ColorAttachment; MAX_COLOR_ATTACHMENTS],
+        color: Vec<ColorAttachment>,
         depth_stencil: DepthStencilAttachment, 
     },
 }
@@ -116,7 +114,7 @@ impl Framebuffer {
         tx: queue::Sender<Id>,
         width: u32,
         height: u32,
-        color: [ColorAttachment; MAX_COLOR_ATTACHMENTS],
+        color: Vec<ColorAttachment>,
         depth_stencil: DepthStencilAttachment,
     ) -> Self {
         Self {
@@ -1,4 +1,4 @@
-use std::{ffi, os, ptr, rc};
+use std::{cmp, ffi, os, ptr, rc};
 
 // Import OpenGL bindings.
 include!(concat!(env!("OUT_DIR"), "/gl.rs"));
@@ -13,7 +13,16 @@ impl Backend {
     pub fn load<F>(mut func: F) -> Self
         where F: FnMut(&str) -> *const ()
     {
-        let gl = rc::Rc::new(Gl::load_with(|sym| func(sym) as *const _));
+        let gl = rc::Rc::new(Gl::load_with(|sym| {
+            let addr = func(sym);
+            if addr.is_null() && sym.ends_with("ARB") {
+                // Extension functions promoted to core may only be
+                // exported under their core name.
+                func(&sym[.. sym.len() - 3]) as *const _
+            } else {
+                addr as *const _
+            }
+        }));
         Backend { gl }
     }
 
@@ -27,6 +36,31 @@ impl Backend {
         }
     }
 
+    // State queries
+
+    /// Corresponds to `glGetIntegerv`.
+    pub fn get_integerv(&self, param: u32) -> i32 {
+        let mut value = 0;
+        unsafe {
+            trace!(target: "gl", "glGetIntegerv{:?} ", (param,));
+            self.gl.GetIntegerv(param, &mut value as *mut _);
+            trace!(target: "gl", "=> {}", value);
+        }
+        self.check_error();
+        value
+    }
+
+    /// Corresponds to `glGetStringi`.
+    pub fn get_string_i(&self, name: u32, index: u32) -> String {
+        trace!(target: "gl", "glGetStringi{:?}", (name, index));
+        let string = unsafe {
+            let ptr = self.gl.GetStringi(name, index);
+            ffi::CStr::from_ptr(ptr as *const _).to_string_lossy().into_owned()
+        };
+        self.check_error();
+        string
+    }
+
     // Pipeline state operations
 
     /// Corresponds to `glClear`.
@@ -74,6 +108,90 @@ impl Backend {
         self.check_error();
     }
 
+    /// Corresponds to `glEnablei`.
+    pub fn enablei(&self, state: u32, index: u32) {
+        trace!(target: "gl", "glEnablei{:?}", (state, index));
+        unsafe {
+            self.gl.Enablei(state, index);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDisablei`.
+    pub fn disablei(&self, state: u32, index: u32) {
+        trace!(target: "gl", "glDisablei{:?}", (state, index));
+        unsafe {
+            self.gl.Disablei(state, index);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glBlendColor`.
+    pub fn blend_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        trace!(target: "gl", "glBlendColor{:?}", (r, g, b, a));
+        unsafe {
+            self.gl.BlendColor(r, g, b, a);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glBlendEquationSeparate`.
+    pub fn blend_equation_separate(&self, rgb: u32, alpha: u32) {
+        trace!(target: "gl", "glBlendEquationSeparate{:?}", (rgb, alpha));
+        unsafe {
+            self.gl.BlendEquationSeparate(rgb, alpha);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glBlendFuncSeparate`.
+    pub fn blend_func_separate(
+        &self,
+        src_rgb: u32,
+        dst_rgb: u32,
+        src_alpha: u32,
+        dst_alpha: u32,
+    ) {
+        trace!(
+            target: "gl",
+            "glBlendFuncSeparate{:?}",
+            (src_rgb, dst_rgb, src_alpha, dst_alpha),
+        );
+        unsafe {
+            self.gl.BlendFuncSeparate(src_rgb, dst_rgb, src_alpha, dst_alpha);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glBlendEquationSeparatei`.
+    pub fn blend_equation_separate_i(&self, buf: u32, rgb: u32, alpha: u32) {
+        trace!(target: "gl", "glBlendEquationSeparatei{:?}", (buf, rgb, alpha));
+        unsafe {
+            self.gl.BlendEquationSeparateiARB(buf, rgb, alpha);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glBlendFuncSeparatei`.
+    pub fn blend_func_separate_i(
+        &self,
+        buf: u32,
+        src_rgb: u32,
+        dst_rgb: u32,
+        src_alpha: u32,
+        dst_alpha: u32,
+    ) {
+        trace!(
+            target: "gl",
+            "glBlendFuncSeparatei{:?}",
+            (buf, src_rgb, dst_rgb, src_alpha, dst_alpha),
+        );
+        unsafe {
+            self.gl.BlendFuncSeparateiARB(buf, src_rgb, dst_rgb, src_alpha, dst_alpha);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glCullFace`.
     pub fn cull_face(&self, opt: u32) {
         trace!(target: "gl", "glCullFace{:?}", (opt,));
@@ -101,6 +219,33 @@ impl Backend {
         self.check_error();
     }
 
+    /// Corresponds to `glPrimitiveRestartIndex`.
+    pub fn primitive_restart_index(&self, index: u32) {
+        trace!(target: "gl", "glPrimitiveRestartIndex{:?}", (index,));
+        unsafe {
+            self.gl.PrimitiveRestartIndex(index);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDepthMask`.
+    pub fn depth_mask(&self, flag: bool) {
+        trace!(target: "gl", "glDepthMask{:?}", (flag,));
+        unsafe {
+            self.gl.DepthMask(flag as _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDepthRange`.
+    pub fn depth_range(&self, near: f64, far: f64) {
+        trace!(target: "gl", "glDepthRange{:?}", (near, far));
+        unsafe {
+            self.gl.DepthRange(near, far);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glPolygonMode`.
     pub fn polygon_mode(&self, face: u32, mode: u32) {
         trace!(target: "gl", "glPolygonMode{:?}", (face, mode));
@@ -139,6 +284,15 @@ impl Backend {
 
     // Buffer operations
 
+    /// Corresponds to `glDeleteBuffers(1)`.
+    pub fn delete_buffer(&self, id: u32) {
+        unsafe {
+            trace!(target: "gl", "glDeleteBuffers{:?}", (1, id));
+            self.gl.DeleteBuffers(1, &id as *const _);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glGenBuffer`.
     pub fn gen_buffer(&self) -> u32 {
         let mut id: u32 = 0;
@@ -178,8 +332,117 @@ impl Backend {
         self.check_error();
     }
 
+    /// Corresponds to `glGetBufferSubData`.
+    pub fn get_buffer_sub_data<T>(&self, ty: u32, off: usize, len: usize, ptr: *mut T) {
+        unsafe {
+            trace!(target: "gl", "glGetBufferSubData{:?}", (ty, off, len, ptr));
+            self.gl.GetBufferSubData(ty, off as _, len as _, ptr as *mut _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glMapBufferRange`.
+    pub fn map_buffer_range(
+        &self,
+        ty: u32,
+        off: usize,
+        len: usize,
+        access: u32,
+    ) -> *mut os::raw::c_void {
+        let ptr;
+        unsafe {
+            trace!(target: "gl", "glMapBufferRange{:?} ", (ty, off, len, access));
+            ptr = self.gl.MapBufferRange(ty, off as _, len as _, access);
+            trace!(target: "gl", "=> {:?}", ptr);
+        }
+        self.check_error();
+        ptr
+    }
+
+    /// Corresponds to `glUnmapBuffer`.
+    pub fn unmap_buffer(&self, ty: u32) -> bool {
+        let result;
+        unsafe {
+            trace!(target: "gl", "glUnmapBuffer{:?} ", (ty,));
+            result = self.gl.UnmapBuffer(ty);
+            trace!(target: "gl", "=> {}", result);
+        }
+        self.check_error();
+        result != 0
+    }
+
+    /// Corresponds to `glCopyBufferSubData`.
+    pub fn copy_buffer_sub_data(
+        &self,
+        read_target: u32,
+        write_target: u32,
+        read_offset: usize,
+        write_offset: usize,
+        size: usize,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glCopyBufferSubData{:?}",
+                (read_target, write_target, read_offset, write_offset, size),
+            );
+            self.gl.CopyBufferSubData(
+                read_target,
+                write_target,
+                read_offset as _,
+                write_offset as _,
+                size as _,
+            );
+        }
+        self.check_error();
+    }
+
+    // Synchronization operations
+
+    /// Corresponds to `glFenceSync(GL_SYNC_GPU_COMMANDS_COMPLETE, 0)`.
+    pub fn fence_sync(&self) -> usize {
+        let sync;
+        unsafe {
+            trace!(target: "gl", "glFenceSync{:?} ", (SYNC_GPU_COMMANDS_COMPLETE, 0));
+            sync = self.gl.FenceSync(SYNC_GPU_COMMANDS_COMPLETE, 0);
+            trace!(target: "gl", "=> {:?}", sync);
+        }
+        self.check_error();
+        sync as usize
+    }
+
+    /// Corresponds to `glDeleteSync`.
+    pub fn delete_sync(&self, sync: usize) {
+        unsafe {
+            trace!(target: "gl", "glDeleteSync{:?}", (sync,));
+            self.gl.DeleteSync(sync as _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glClientWaitSync`.
+    pub fn client_wait_sync(&self, sync: usize, flags: u32, timeout: u64) -> u32 {
+        let result;
+        unsafe {
+            trace!(target: "gl", "glClientWaitSync{:?} ", (sync, flags, timeout));
+            result = self.gl.ClientWaitSync(sync as types::GLsync, flags, timeout);
+            trace!(target: "gl", "=> {}", result);
+        }
+        self.check_error();
+        result
+    }
+
     // Vertex array operations
 
+    /// Corresponds to `glDeleteVertexArrays(1)`.
+    pub fn delete_vertex_array(&self, id: u32) {
+        unsafe {
+            trace!(target: "gl", "glDeleteVertexArrays{:?}", (1, id));
+            self.gl.DeleteVertexArrays(1, &id as *const _);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glGenVertexArrays`.
     pub fn gen_vertex_array(&self) -> u32 {
         let mut id: u32 = 0;
@@ -210,6 +473,33 @@ impl Backend {
         self.check_error();
     }
 
+    /// Corresponds to `glVertexAttribIPointer`.
+    pub fn vertex_attrib_i_pointer(&self, id: u8, sz: i32, ty: u32, stride: i32, off: usize) {
+        unsafe {
+            trace!(target: "gl", "glVertexAttribIPointer{:?}", (id, sz, ty, stride, off));
+            self.gl.VertexAttribIPointer(id as _, sz as _, ty, stride as _, off as *const _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glVertexAttribLPointer`.
+    pub fn vertex_attrib_l_pointer(&self, id: u8, sz: i32, ty: u32, stride: i32, off: usize) {
+        unsafe {
+            trace!(target: "gl", "glVertexAttribLPointer{:?}", (id, sz, ty, stride, off));
+            self.gl.VertexAttribLPointer(id as _, sz as _, ty, stride as _, off as *const _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glVertexAttribDivisor`.
+    pub fn vertex_attrib_divisor(&self, id: u8, divisor: u32) {
+        unsafe {
+            trace!(target: "gl", "glVertexAttribDivisor{:?}", (id, divisor));
+            self.gl.VertexAttribDivisorARB(id as _, divisor);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glEnableVertexAttribArray`.
     pub fn enable_vertex_attrib_array(&self, idx: u8) {
         unsafe {
@@ -219,8 +509,53 @@ impl Backend {
         self.check_error();
     }
 
+    /// Corresponds to `glVertexAttrib4f`.
+    pub fn vertex_attrib_4f(&self, idx: u8, value: [f32; 4]) {
+        unsafe {
+            trace!(target: "gl", "glVertexAttrib4f{:?}", (idx, value));
+            self.gl.VertexAttrib4f(idx as _, value[0], value[1], value[2], value[3]);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glVertexAttribI4i`.
+    pub fn vertex_attrib_i_4i(&self, idx: u8, value: [i32; 4]) {
+        unsafe {
+            trace!(target: "gl", "glVertexAttribI4i{:?}", (idx, value));
+            self.gl.VertexAttribI4i(idx as _, value[0], value[1], value[2], value[3]);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glVertexAttribI4ui`.
+    pub fn vertex_attrib_i_4ui(&self, idx: u8, value: [u32; 4]) {
+        unsafe {
+            trace!(target: "gl", "glVertexAttribI4ui{:?}", (idx, value));
+            self.gl.VertexAttribI4ui(idx as _, value[0], value[1], value[2], value[3]);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDisableVertexAttribArray`.
+    pub fn disable_vertex_attrib_array(&self, idx: u8) {
+        unsafe {
+            trace!(target: "gl", "glDisableVertexAttribArray{:?}", (idx,));
+            self.gl.DisableVertexAttribArray(idx as _);
+        }
+        self.check_error();
+    }
+
     // Framebuffer operations.
 
+    /// Corresponds to `glDeleteFramebuffers(1)`.
+    pub fn delete_framebuffer(&self, id: u32) {
+        unsafe {
+            trace!(target: "gl", "glDeleteFramebuffers{:?}", (1, id));
+            self.gl.DeleteFramebuffers(1, &id as *const _);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glGenFramebuffers(1)`.
     pub fn gen_framebuffer(&self) -> u32 {
         trace!(target: "gl", "glGenFramebuffers(1)");
@@ -232,6 +567,15 @@ impl Backend {
         id
     }
 
+    /// Corresponds to `glDeleteRenderbuffers(1)`.
+    pub fn delete_renderbuffer(&self, id: u32) {
+        unsafe {
+            trace!(target: "gl", "glDeleteRenderbuffers{:?}", (1, id));
+            self.gl.DeleteRenderbuffers(1, &id as *const _);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glGenRenderbuffers(1)`.
     pub fn gen_renderbuffer(&self) -> u32 {
         trace!(target: "gl", "glGenRenderbuffers(1)");
@@ -369,6 +713,15 @@ impl Backend {
         id
     }
 
+    /// Corresponds to `glDeleteShader`.
+    pub fn delete_shader(&self, id: u32) {
+        unsafe {
+            trace!(target: "gl", "glDeleteShader{:?}", (id,));
+            self.gl.DeleteShader(id);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glShaderSource`.
     pub fn shader_source(&self, id: u32, source: &ffi::CStr) {
         unsafe {
@@ -405,6 +758,15 @@ impl Backend {
         id
     }
 
+    /// Corresponds to `glDeleteProgram`.
+    pub fn delete_program(&self, id: u32) {
+        unsafe {
+            trace!(target: "gl", "glDeleteProgram{:?}", (id,));
+            self.gl.DeleteProgram(id);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glAttachShader`.
     pub fn attach_shader(&self, program: u32, shader: u32) {
         unsafe {
@@ -431,6 +793,100 @@ impl Backend {
         }
     }
 
+    /// Corresponds to `glTransformFeedbackVaryings`.
+    pub fn transform_feedback_varyings(
+        &self,
+        program: u32,
+        varyings: &[&ffi::CStr],
+        mode: u32,
+    ) {
+        let ptrs: Vec<_> = varyings.iter().map(|name| name.as_ptr()).collect();
+        trace!(
+            target: "gl",
+            "glTransformFeedbackVaryings{:?}",
+            (program, varyings.len(), varyings, mode),
+        );
+        unsafe {
+            self.gl.TransformFeedbackVaryings(
+                program,
+                ptrs.len() as _,
+                ptrs.as_ptr() as *const _,
+                mode,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glBindAttribLocation`.
+    pub fn bind_attrib_location(&self, program: u32, index: u32, name: &ffi::CStr) {
+        unsafe {
+            trace!(target: "gl", "glBindAttribLocation{:?}", (program, index, name));
+            self.gl.BindAttribLocation(program, index, name.as_ptr() as _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glBindFragDataLocation`.
+    pub fn bind_frag_data_location(&self, program: u32, color: u32, name: &ffi::CStr) {
+        unsafe {
+            trace!(target: "gl", "glBindFragDataLocation{:?}", (program, color, name));
+            self.gl.BindFragDataLocation(program, color, name.as_ptr() as _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glGetProgramiv`.
+    pub fn get_programiv(&self, id: u32, param: u32) -> i32 {
+        let mut value = 0;
+        unsafe {
+            trace!(target: "gl", "glGetProgramiv{:?} ", (id, param));
+            self.gl.GetProgramiv(id, param, &mut value as *mut _);
+            trace!(target: "gl", "=> {}", value);
+        }
+        self.check_error();
+        value
+    }
+
+    /// Corresponds to `glGetActiveAttrib`.
+    ///
+    /// Returns the name, array size, and data type of the attribute.
+    pub fn get_active_attrib(&self, id: u32, index: u32) -> (String, i32, u32) {
+        let max_length = self.get_programiv(id, ACTIVE_ATTRIBUTE_MAX_LENGTH);
+        let mut name = vec![0u8; cmp::max(max_length, 1) as usize];
+        let mut length = 0;
+        let mut size = 0;
+        let mut ty = 0;
+        unsafe {
+            trace!(target: "gl", "glGetActiveAttrib{:?} ", (id, index));
+            self.gl.GetActiveAttrib(
+                id,
+                index,
+                name.len() as _,
+                &mut length as *mut _,
+                &mut size as *mut _,
+                &mut ty as *mut _,
+                name.as_mut_ptr() as *mut _,
+            );
+        }
+        self.check_error();
+        name.truncate(length as usize);
+        let name = String::from_utf8_lossy(&name).into_owned();
+        trace!(target: "gl", "=> {:?}", (&name, size, ty));
+        (name, size, ty)
+    }
+
+    /// Corresponds to `glGetAttribLocation`.
+    pub fn get_attrib_location(&self, id: u32, name: &ffi::CStr) -> i32 {
+        let location;
+        unsafe {
+            trace!(target: "gl", "glGetAttribLocation{:?} ", (id, name));
+            location = self.gl.GetAttribLocation(id, name.as_ptr() as _);
+            trace!(target: "gl", "=> {}", location);
+        }
+        self.check_error();
+        location
+    }
+
     /// Corresponds to `glGetUniformBlockIndex`.
     pub fn get_uniform_block_index(
         &self,
@@ -492,6 +948,15 @@ impl Backend {
 
     // Texture operations
 
+    /// Corresponds to `glDeleteTextures(1)`.
+    pub fn delete_texture(&self, id: u32) {
+        unsafe {
+            trace!(target: "gl", "glDeleteTextures{:?}", (1, id));
+            self.gl.DeleteTextures(1, &id as *const _);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glGenTextures`.
     pub fn gen_texture(&self) -> u32 {
         let mut id = INVALID_INDEX;
@@ -563,6 +1028,15 @@ impl Backend {
         self.check_error();
     }
 
+    /// Corresponds to `glTexBuffer`.
+    pub fn tex_buffer(&self, target: u32, internal_format: u32, buffer: u32) {
+        unsafe {
+            trace!(target: "gl", "glTexBuffer{:?}", (target, internal_format, buffer));
+            self.gl.TexBuffer(target, internal_format, buffer);
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glGetTexImage`.
     pub fn get_tex_image(
         &self,
@@ -623,6 +1097,359 @@ impl Backend {
         self.check_error();
     }
 
+    /// Corresponds to `glBeginTransformFeedback`.
+    pub fn begin_transform_feedback(&self, mode: u32) {
+        unsafe {
+            trace!(target: "gl", "glBeginTransformFeedback{:?}", (mode,));
+            self.gl.BeginTransformFeedback(mode);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glEndTransformFeedback`.
+    pub fn end_transform_feedback(&self) {
+        unsafe {
+            trace!(target: "gl", "glEndTransformFeedback()");
+            self.gl.EndTransformFeedback();
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawElementsBaseVertex`.
+    pub fn draw_elements_base_vertex(
+        &self,
+        mode: u32,
+        offset: usize,
+        count: usize,
+        ty: u32,
+        base_vertex: i32,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glDrawElementsBaseVertex{:?}",
+                (mode, count, ty, offset, base_vertex),
+            );
+            self.gl.DrawElementsBaseVertex(
+                mode,
+                count as _,
+                ty,
+                offset as *const _,
+                base_vertex,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawRangeElements`.
+    pub fn draw_range_elements(
+        &self,
+        mode: u32,
+        start: usize,
+        end: usize,
+        offset: usize,
+        count: usize,
+        ty: u32,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glDrawRangeElements{:?}",
+                (mode, start, end, count, ty, offset),
+            );
+            self.gl.DrawRangeElements(
+                mode,
+                start as _,
+                end as _,
+                count as _,
+                ty,
+                offset as *const _,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawRangeElementsBaseVertex`.
+    pub fn draw_range_elements_base_vertex(
+        &self,
+        mode: u32,
+        start: usize,
+        end: usize,
+        offset: usize,
+        count: usize,
+        ty: u32,
+        base_vertex: i32,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glDrawRangeElementsBaseVertex{:?}",
+                (mode, start, end, count, ty, offset, base_vertex),
+            );
+            self.gl.DrawRangeElementsBaseVertex(
+                mode,
+                start as _,
+                end as _,
+                count as _,
+                ty,
+                offset as *const _,
+                base_vertex,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glMultiDrawArrays`.
+    pub fn multi_draw_arrays(&self, mode: u32, offsets: &[i32], counts: &[i32]) {
+        debug_assert_eq!(offsets.len(), counts.len());
+        unsafe {
+            trace!(target: "gl", "glMultiDrawArrays{:?}", (mode, offsets, counts));
+            self.gl.MultiDrawArrays(
+                mode,
+                offsets.as_ptr(),
+                counts.as_ptr(),
+                counts.len() as _,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glMultiDrawElements`.
+    pub fn multi_draw_elements(
+        &self,
+        mode: u32,
+        offsets: &[usize],
+        counts: &[i32],
+        ty: u32,
+    ) {
+        debug_assert_eq!(offsets.len(), counts.len());
+        unsafe {
+            trace!(target: "gl", "glMultiDrawElements{:?}", (mode, counts, ty, offsets));
+            self.gl.MultiDrawElements(
+                mode,
+                counts.as_ptr(),
+                ty,
+                offsets.as_ptr() as *const *const _,
+                counts.len() as _,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glMultiDrawElementsBaseVertex`.
+    pub fn multi_draw_elements_base_vertex(
+        &self,
+        mode: u32,
+        offsets: &[usize],
+        counts: &[i32],
+        ty: u32,
+        base_vertices: &[i32],
+    ) {
+        debug_assert_eq!(offsets.len(), counts.len());
+        debug_assert_eq!(offsets.len(), base_vertices.len());
+        unsafe {
+            trace!(
+                target: "gl",
+                "glMultiDrawElementsBaseVertex{:?}",
+                (mode, counts, ty, offsets, base_vertices),
+            );
+            self.gl.MultiDrawElementsBaseVertex(
+                mode,
+                counts.as_ptr(),
+                ty,
+                offsets.as_ptr() as *const *const _,
+                counts.len() as _,
+                base_vertices.as_ptr(),
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawArraysInstanced`.
+    pub fn draw_arrays_instanced(&self, mode: u32, offset: usize, count: usize, instances: usize) {
+        unsafe {
+            trace!(target: "gl", "glDrawArraysInstanced{:?}", (mode, offset, count, instances));
+            self.gl.DrawArraysInstanced(mode, offset as _, count as _, instances as _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawElementsInstanced`.
+    pub fn draw_elements_instanced(
+        &self,
+        mode: u32,
+        offset: usize,
+        count: usize,
+        ty: u32,
+        instances: usize,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glDrawElementsInstanced{:?}",
+                (mode, count, ty, offset, instances),
+            );
+            self.gl.DrawElementsInstanced(
+                mode,
+                count as _,
+                ty,
+                offset as *const _,
+                instances as _,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawElementsInstancedBaseVertex`.
+    pub fn draw_elements_instanced_base_vertex(
+        &self,
+        mode: u32,
+        offset: usize,
+        count: usize,
+        ty: u32,
+        instances: usize,
+        base_vertex: i32,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glDrawElementsInstancedBaseVertex{:?}",
+                (mode, count, ty, offset, instances, base_vertex),
+            );
+            self.gl.DrawElementsInstancedBaseVertex(
+                mode,
+                count as _,
+                ty,
+                offset as *const _,
+                instances as _,
+                base_vertex,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawArraysInstancedBaseInstance`.
+    pub fn draw_arrays_instanced_base_instance(
+        &self,
+        mode: u32,
+        offset: usize,
+        count: usize,
+        instances: usize,
+        base_instance: usize,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glDrawArraysInstancedBaseInstance{:?}",
+                (mode, offset, count, instances, base_instance),
+            );
+            self.gl.DrawArraysInstancedBaseInstance(
+                mode,
+                offset as _,
+                count as _,
+                instances as _,
+                base_instance as _,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawElementsInstancedBaseInstance`.
+    pub fn draw_elements_instanced_base_instance(
+        &self,
+        mode: u32,
+        offset: usize,
+        count: usize,
+        ty: u32,
+        instances: usize,
+        base_instance: usize,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glDrawElementsInstancedBaseInstance{:?}",
+                (mode, count, ty, offset, instances, base_instance),
+            );
+            self.gl.DrawElementsInstancedBaseInstance(
+                mode,
+                count as _,
+                ty,
+                offset as *const _,
+                instances as _,
+                base_instance as _,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawArraysIndirect`.
+    pub fn draw_arrays_indirect(&self, mode: u32, offset: usize) {
+        unsafe {
+            trace!(target: "gl", "glDrawArraysIndirect{:?}", (mode, offset));
+            self.gl.DrawArraysIndirect(mode, offset as *const _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glDrawElementsIndirect`.
+    pub fn draw_elements_indirect(&self, mode: u32, ty: u32, offset: usize) {
+        unsafe {
+            trace!(target: "gl", "glDrawElementsIndirect{:?}", (mode, ty, offset));
+            self.gl.DrawElementsIndirect(mode, ty, offset as *const _);
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glMultiDrawArraysIndirect`.
+    pub fn multi_draw_arrays_indirect(
+        &self,
+        mode: u32,
+        offset: usize,
+        count: usize,
+        stride: usize,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glMultiDrawArraysIndirect{:?}",
+                (mode, offset, count, stride),
+            );
+            self.gl.MultiDrawArraysIndirect(
+                mode,
+                offset as *const _,
+                count as _,
+                stride as _,
+            );
+        }
+        self.check_error();
+    }
+
+    /// Corresponds to `glMultiDrawElementsIndirect`.
+    pub fn multi_draw_elements_indirect(
+        &self,
+        mode: u32,
+        ty: u32,
+        offset: usize,
+        count: usize,
+        stride: usize,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glMultiDrawElementsIndirect{:?}",
+                (mode, ty, offset, count, stride),
+            );
+            self.gl.MultiDrawElementsIndirect(
+                mode,
+                ty,
+                offset as *const _,
+                count as _,
+                stride as _,
+            );
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glUseProgram`.
     pub fn use_program(&self, id: u32) {
         unsafe {
@@ -641,6 +1468,32 @@ impl Backend {
         self.check_error();
     }
 
+    /// Corresponds to `glBindBufferRange`.
+    pub fn bind_buffer_range(
+        &self,
+        target: u32,
+        binding: u32,
+        id: u32,
+        offset: usize,
+        size: usize,
+    ) {
+        unsafe {
+            trace!(
+                target: "gl",
+                "glBindBufferRange{:?}",
+                (target, binding, id, offset, size),
+            );
+            self.gl.BindBufferRange(
+                target,
+                binding,
+                id,
+                offset as _,
+                size as _,
+            );
+        }
+        self.check_error();
+    }
+
     /// Corresponds to `glActiveTexture(GL_TEXTURE0 + index)`.
     pub fn active_texture(&self, index: u32) {
         unsafe {
@@ -0,0 +1,461 @@
+//! Sub-allocation of large GPU buffers.
+
+use buffer::{Accessor, Buffer, Format, Kind, Slice, Usage};
+use std::{cmp, collections, fmt};
+use vec_map::VecMap;
+
+use Factory;
+
+/// A contiguous byte range within a heap block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Range {
+    /// Byte offset into the block.
+    offset: usize,
+
+    /// Number of bytes.
+    size: usize,
+}
+
+impl Range {
+    /// Returns the byte offset one past the end of the range.
+    fn end(&self) -> usize {
+        self.offset + self.size
+    }
+}
+
+/// A large buffer divided into sub-allocations.
+struct Block {
+    /// The underlying GPU buffer.
+    buffer: Buffer,
+
+    /// Unused ranges, sorted by offset and never adjacent.
+    free: Vec<Range>,
+}
+
+impl Block {
+    /// Attempts to carve out `size` bytes aligned to `alignment`.
+    fn allocate(&mut self, size: usize, alignment: usize) -> Option<usize> {
+        for index in 0 .. self.free.len() {
+            let range = self.free[index];
+            let offset = align_up(range.offset, alignment);
+            if offset + size > range.end() {
+                continue;
+            }
+            self.free.remove(index);
+            let tail = Range {
+                offset: offset + size,
+                size: range.end() - (offset + size),
+            };
+            if tail.size > 0 {
+                self.free.insert(index, tail);
+            }
+            let head = Range {
+                offset: range.offset,
+                size: offset - range.offset,
+            };
+            if head.size > 0 {
+                self.free.insert(index, head);
+            }
+            return Some(offset);
+        }
+        None
+    }
+
+    /// Returns a range to the free list, merging it with its neighbours.
+    fn release(&mut self, range: Range) {
+        let index = self.free
+            .iter()
+            .position(|free| free.offset > range.offset)
+            .unwrap_or(self.free.len());
+        self.free.insert(index, range);
+        let next = index + 1;
+        if next < self.free.len() && self.free[index].end() == self.free[next].offset {
+            self.free[index].size += self.free[next].size;
+            self.free.remove(next);
+        }
+        if index > 0 && self.free[index - 1].end() == self.free[index].offset {
+            self.free[index - 1].size += self.free[index].size;
+            self.free.remove(index);
+        }
+    }
+}
+
+/// Book-keeping for a live allocation.
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    /// The kind of block the allocation lives in.
+    kind: Kind,
+
+    /// Index of the block within its kind.
+    block: usize,
+
+    /// Byte offset and size within the block.
+    range: Range,
+
+    /// Required byte alignment of the offset.
+    alignment: usize,
+}
+
+/// A handle to a region of memory sub-allocated from a [`Heap`].
+///
+/// The handle must be returned with [`Heap::free`] to reclaim the memory.
+///
+/// [`Heap`]: struct.Heap.html
+/// [`Heap::free`]: struct.Heap.html#method.free
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Allocation {
+    /// Key into the heap entry map.
+    id: usize,
+}
+
+/// Heap usage statistics.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Statistics {
+    /// Number of GPU buffers owned by the heap.
+    pub blocks: usize,
+
+    /// Total number of bytes owned by the heap.
+    pub capacity: usize,
+
+    /// Number of bytes currently allocated.
+    pub allocated: usize,
+
+    /// Number of live allocations.
+    pub allocations: usize,
+
+    /// Number of disjoint free ranges.
+    pub free_ranges: usize,
+
+    /// Size of the largest free range in bytes.
+    pub largest_free_range: usize,
+}
+
+/// Owns a handful of large GPU buffers per buffer [`Kind`] and sub-allocates
+/// regions of them.
+///
+/// Many meshes packed into one heap block may share a single
+/// [`VertexArray`], using the allocation offset as the base vertex or
+/// element offset of each draw call.
+///
+/// [`Kind`]: ../buffer/enum.Kind.html
+/// [`VertexArray`]: ../vertex_array/struct.VertexArray.html
+pub struct Heap {
+    /// Used to create blocks and move allocations.
+    factory: Factory,
+
+    /// The minimum size of newly created blocks.
+    block_size: usize,
+
+    /// The data usage hint of newly created blocks.
+    usage: Usage,
+
+    /// Blocks grouped by buffer kind.
+    blocks: collections::BTreeMap<Kind, Vec<Block>>,
+
+    /// Live allocations.
+    entries: VecMap<Entry>,
+
+    /// Recycled allocation IDs.
+    free_ids: Vec<usize>,
+}
+
+impl Heap {
+    /// Constructor.
+    pub(crate) fn new(factory: Factory, block_size: usize, usage: Usage) -> Self {
+        Self {
+            factory,
+            block_size,
+            usage,
+            blocks: collections::BTreeMap::new(),
+            entries: VecMap::new(),
+            free_ids: Vec::new(),
+        }
+    }
+
+    /// Sub-allocate `size` bytes from a block of the given kind with the
+    /// offset aligned to `alignment` bytes.
+    ///
+    /// A new block is created if no existing block has room.
+    pub fn allocate(
+        &mut self,
+        kind: Kind,
+        size: usize,
+        alignment: usize,
+    ) -> Allocation {
+        assert!(alignment > 0, "allocation alignment must be non-zero");
+        let blocks = self.blocks.entry(kind).or_insert_with(Vec::new);
+        let mut found = None;
+        for (index, block) in blocks.iter_mut().enumerate() {
+            if let Some(offset) = block.allocate(size, alignment) {
+                found = Some((index, offset));
+                break;
+            }
+        }
+        let (block, offset) = match found {
+            Some(location) => location,
+            None => {
+                let capacity = cmp::max(self.block_size, size);
+                let buffer = self.factory
+                    .uninitialized_buffer(capacity, kind, self.usage);
+                let mut block = Block {
+                    buffer,
+                    free: vec![Range { offset: 0, size: capacity }],
+                };
+                let offset = block.allocate(size, alignment).unwrap();
+                blocks.push(block);
+                (blocks.len() - 1, offset)
+            }
+        };
+        let entry = Entry {
+            kind,
+            block,
+            range: Range { offset, size },
+            alignment,
+        };
+        let id = self.free_ids.pop().unwrap_or(self.entries.len());
+        self.entries.insert(id, entry);
+        Allocation { id }
+    }
+
+    /// Return an allocation to the heap.
+    pub fn free(&mut self, allocation: Allocation) {
+        let entry = self.entries
+            .remove(allocation.id)
+            .expect("invalid allocation");
+        self.free_ids.push(allocation.id);
+        let blocks = self.blocks.get_mut(&entry.kind).unwrap();
+        blocks[entry.block].release(entry.range);
+    }
+
+    /// Returns the entry for an allocation.
+    fn entry(&self, allocation: &Allocation) -> &Entry {
+        self.entries.get(allocation.id).expect("invalid allocation")
+    }
+
+    /// Returns the block buffer an allocation lives in.
+    ///
+    /// All allocations from the same block share this buffer.
+    pub fn buffer(&self, allocation: &Allocation) -> &Buffer {
+        let entry = self.entry(allocation);
+        &self.blocks[&entry.kind][entry.block].buffer
+    }
+
+    /// Returns the byte offset of an allocation into its block buffer.
+    ///
+    /// The offset may change after [`defragment`] is called.
+    ///
+    /// [`defragment`]: #method.defragment
+    pub fn offset(&self, allocation: &Allocation) -> usize {
+        self.entry(allocation).range.offset
+    }
+
+    /// Returns the number of bytes in an allocation.
+    pub fn size(&self, allocation: &Allocation) -> usize {
+        self.entry(allocation).range.size
+    }
+
+    /// Returns a [`Slice`] covering an allocation.
+    ///
+    /// [`Slice`]: ../buffer/struct.Slice.html
+    pub fn slice(&self, allocation: &Allocation) -> Slice {
+        let range = self.entry(allocation).range;
+        Slice::new(self.buffer(allocation), range.offset, range.size)
+    }
+
+    /// Creates an [`Accessor`] reading from an allocation, where `offset`
+    /// is relative to the start of the allocation.
+    ///
+    /// [`Accessor`]: ../buffer/struct.Accessor.html
+    pub fn accessor<F: Into<Format>>(
+        &self,
+        allocation: &Allocation,
+        format: F,
+        offset: usize,
+        stride: usize,
+    ) -> Accessor {
+        let format = format.into();
+        let range = self.entry(allocation).range;
+        assert!(
+            offset + format.byte_size() <= range.size,
+            "accessor reads outside of allocation",
+        );
+        Accessor::new(
+            self.buffer(allocation).clone(),
+            format,
+            range.offset + offset,
+            stride,
+        )
+    }
+
+    /// Compacts every block by moving live allocations towards the start
+    /// of their block with GPU copies, coalescing the free space at the end.
+    ///
+    /// Allocation offsets change as a result, so any accessors or draw
+    /// calls derived from them must be recreated.
+    pub fn defragment(&mut self) {
+        let mut ids: Vec<usize> = self.entries.keys().collect();
+        ids.sort_by_key(|&id| {
+            let entry = &self.entries[id];
+            (entry.kind, entry.block, entry.range.offset)
+        });
+
+        // Plan every move first so that overlapping moves can share a single
+        // scratch buffer, since `glCopyBufferSubData` forbids overlap.
+        let mut cursor = collections::BTreeMap::<(Kind, usize), usize>::new();
+        let mut moves = Vec::new();
+        let mut scratch_size = 0;
+        for id in ids {
+            let entry = self.entries[id];
+            let start = cursor.entry((entry.kind, entry.block)).or_insert(0);
+            let offset = align_up(*start, entry.alignment);
+            if offset < entry.range.offset {
+                if offset + entry.range.size > entry.range.offset {
+                    scratch_size = cmp::max(scratch_size, entry.range.size);
+                }
+                moves.push((id, offset));
+            }
+            *start = cmp::min(offset, entry.range.offset) + entry.range.size;
+        }
+        let scratch = if scratch_size > 0 {
+            Some(self.factory.uninitialized_buffer(
+                scratch_size,
+                Kind::CopyRead,
+                Usage::StreamCopy,
+            ))
+        } else {
+            None
+        };
+
+        for (id, offset) in moves {
+            let entry = self.entries[id];
+            let buffer = &self.blocks[&entry.kind][entry.block].buffer;
+            let src = Slice::new(buffer, entry.range.offset, entry.range.size);
+            let dst = Slice::new(buffer, offset, entry.range.size);
+            if offset + entry.range.size > entry.range.offset {
+                let temp = Slice::new(scratch.as_ref().unwrap(), 0, entry.range.size);
+                self.factory.copy_buffer(src, temp);
+                self.factory.copy_buffer(temp, dst);
+            } else {
+                self.factory.copy_buffer(src, dst);
+            }
+            self.entries[id].range.offset = offset;
+        }
+        for (&kind, blocks) in self.blocks.iter_mut() {
+            for (index, block) in blocks.iter_mut().enumerate() {
+                let used = cursor.get(&(kind, index)).cloned().unwrap_or(0);
+                let capacity = block.buffer.size();
+                block.free.clear();
+                if used < capacity {
+                    let size = capacity - used;
+                    block.free.push(Range { offset: used, size });
+                }
+            }
+        }
+    }
+
+    /// Returns heap usage statistics.
+    pub fn statistics(&self) -> Statistics {
+        let mut stats = Statistics::default();
+        for block in self.blocks.values().flat_map(|blocks| blocks.iter()) {
+            stats.blocks += 1;
+            stats.capacity += block.buffer.size();
+            stats.free_ranges += block.free.len();
+            for range in &block.free {
+                stats.largest_free_range =
+                    cmp::max(stats.largest_free_range, range.size);
+            }
+        }
+        for entry in self.entries.values() {
+            stats.allocations += 1;
+            stats.allocated += entry.range.size;
+        }
+        stats
+    }
+}
+
+impl fmt::Debug for Heap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[derive(Debug)]
+        struct Heap {
+            block_size: usize,
+            usage: Usage,
+            statistics: Statistics,
+        }
+
+        Heap {
+            block_size: self.block_size,
+            usage: self.usage,
+            statistics: self.statistics(),
+        }.fmt(f)
+    }
+}
+
+/// Rounds `value` up to the nearest multiple of `alignment`.
+fn align_up(value: usize, alignment: usize) -> usize {
+    (value + alignment - 1) / alignment * alignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use queue::Queue;
+
+    fn block(size: usize) -> Block {
+        let queue = Queue::new();
+        Block {
+            buffer: Buffer::new(0, Kind::Array, size, Usage::StaticDraw, queue.tx()),
+            free: vec![Range { offset: 0, size }],
+        }
+    }
+
+    #[test]
+    fn allocate_splits_tail() {
+        let mut block = block(64);
+        assert_eq!(block.allocate(16, 1), Some(0));
+        assert_eq!(block.free, vec![Range { offset: 16, size: 48 }]);
+    }
+
+    #[test]
+    fn allocate_respects_alignment() {
+        let mut block = block(64);
+        assert_eq!(block.allocate(3, 1), Some(0));
+        assert_eq!(block.allocate(8, 16), Some(16));
+        assert_eq!(
+            block.free,
+            vec![Range { offset: 3, size: 13 }, Range { offset: 24, size: 40 }],
+        );
+        assert_eq!(block.allocate(4, 4), Some(4));
+        assert_eq!(
+            block.free,
+            vec![
+                Range { offset: 3, size: 1 },
+                Range { offset: 8, size: 8 },
+                Range { offset: 24, size: 40 },
+            ],
+        );
+    }
+
+    #[test]
+    fn allocate_fails_when_full() {
+        let mut block = block(32);
+        assert_eq!(block.allocate(32, 1), Some(0));
+        assert!(block.free.is_empty());
+        assert_eq!(block.allocate(1, 1), None);
+    }
+
+    #[test]
+    fn release_coalesces_neighbours() {
+        let mut block = block(48);
+        assert_eq!(block.allocate(16, 1), Some(0));
+        assert_eq!(block.allocate(16, 1), Some(16));
+        assert_eq!(block.allocate(16, 1), Some(32));
+        block.release(Range { offset: 0, size: 16 });
+        block.release(Range { offset: 32, size: 16 });
+        assert_eq!(
+            block.free,
+            vec![Range { offset: 0, size: 16 }, Range { offset: 32, size: 16 }],
+        );
+        block.release(Range { offset: 16, size: 16 });
+        assert_eq!(block.free, vec![Range { offset: 0, size: 48 }]);
+    }
+}
@@ -52,6 +52,23 @@ pub mod format {
     }
 
     impl Format {
+        /// Returns the number of bytes occupied by one pixel.
+        pub fn pixel_size(&self) -> usize {
+            match *self {
+                Format::F32(order) => 4 * order.channels(),
+                Format::U8(order) => order.channels(),
+                Format::U32(order) => 4 * order.channels(),
+            }
+        }
+
+        /// Returns the number of bytes occupied by an image of the given
+        /// dimensions, with rows padded to the default 4-byte pixel
+        /// store alignment.
+        pub fn image_size(&self, width: usize, height: usize) -> usize {
+            let row = (width * self.pixel_size() + 3) / 4 * 4;
+            row * height
+        }
+
         pub(crate) fn as_gl_enums(&self) -> (u32, u32) {
             match *self {
                 Format::F32(F32::R) => (gl::FLOAT, gl::RED),
@@ -161,4 +178,28 @@ pub mod format {
         /// `[B, G, R, A; R, ...]`
         Bgra,
     }
+
+    macro_rules! impl_channels {
+        ( $($ident:ident,)* ) => {
+            $(
+                impl $ident {
+                    /// Returns the number of channels per pixel.
+                    pub fn channels(self) -> usize {
+                        match self {
+                            $ident::R => 1,
+                            $ident::Rg => 2,
+                            $ident::Rgb | $ident::Bgr => 3,
+                            $ident::Rgba | $ident::Bgra => 4,
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_channels!(
+        U8,
+        U32,
+        F32,
+    );
 }
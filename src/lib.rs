@@ -1,6 +1,7 @@
 //! Lean OpenGL 3.2 wrapper library.
 
 extern crate crossbeam_channel;
+#[cfg(feature = "derive")] extern crate gpu_derive;
 #[macro_use] extern crate log;
 extern crate vec_map;
 
@@ -15,7 +16,9 @@ mod util;
 
 pub mod buffer;
 pub mod draw_call;
+pub mod fence;
 pub mod framebuffer;
+pub mod heap;
 pub mod image;
 pub mod program;
 pub mod pipeline;
@@ -52,15 +55,24 @@ pub use buffer::Accessor;
 #[doc(inline)]
 pub use buffer::Buffer;
 
+#[doc(inline)]
+pub use buffer::TypedBuffer;
+
 #[doc(inline)]
 pub use draw_call::DrawCall;
 
+#[doc(inline)]
+pub use draw_call::MultiDrawCall;
+
 #[doc(inline)]
 pub use draw_call::Primitive;
 
 #[doc(inline)]
 pub use factory::Factory;
 
+#[doc(inline)]
+pub use fence::Fence;
+
 #[doc(inline)]
 pub use framebuffer::Framebuffer;
 
@@ -79,6 +91,9 @@ pub use renderbuffer::Renderbuffer;
 #[doc(inline)]
 pub use texture::Texture2;
 
+#[doc(inline)]
+pub use texture::TextureBuffer;
+
 #[doc(inline)]
 pub use sampler::Sampler2;
 
@@ -1,16 +1,78 @@
 //! Graphics pipeline states.
 
 use gl;
+use std::{cmp, hash};
 
 /// The default pipeline states.
 pub const DEFAULT_STATE: State = State {
+    blending: Blending::None,
+    blend_color: BlendColor { r: 0.0, g: 0.0, b: 0.0, a: 0.0 },
     culling: Culling::Back,
     depth_test: DepthTest::LessThan,
+    depth_write: true,
+    depth_range: DepthRange { near: 0.0, far: 1.0 },
     front_face: FrontFace::CounterClockwise,
     polygon_mode: PolygonMode::Fill,
+    primitive_restart: PrimitiveRestart::Disabled,
     viewport: Viewport::Max,
 };
 
+/// Conventional alpha blending for straight (non-premultiplied) alpha.
+pub const BLEND_ALPHA: Blend = Blend {
+    rgb: BlendFunction {
+        equation: BlendEquation::Add,
+        source: BlendFactor::SrcAlpha,
+        destination: BlendFactor::OneMinusSrcAlpha,
+    },
+    alpha: BlendFunction {
+        equation: BlendEquation::Add,
+        source: BlendFactor::One,
+        destination: BlendFactor::OneMinusSrcAlpha,
+    },
+};
+
+/// Alpha blending for colors premultiplied by their alpha.
+pub const BLEND_PREMULTIPLIED_ALPHA: Blend = Blend {
+    rgb: BlendFunction {
+        equation: BlendEquation::Add,
+        source: BlendFactor::One,
+        destination: BlendFactor::OneMinusSrcAlpha,
+    },
+    alpha: BlendFunction {
+        equation: BlendEquation::Add,
+        source: BlendFactor::One,
+        destination: BlendFactor::OneMinusSrcAlpha,
+    },
+};
+
+/// Adds the incoming color to the stored color.
+pub const BLEND_ADDITIVE: Blend = Blend {
+    rgb: BlendFunction {
+        equation: BlendEquation::Add,
+        source: BlendFactor::One,
+        destination: BlendFactor::One,
+    },
+    alpha: BlendFunction {
+        equation: BlendEquation::Add,
+        source: BlendFactor::One,
+        destination: BlendFactor::One,
+    },
+};
+
+/// Multiplies the stored color by the incoming color.
+pub const BLEND_MULTIPLY: Blend = Blend {
+    rgb: BlendFunction {
+        equation: BlendEquation::Add,
+        source: BlendFactor::DstColor,
+        destination: BlendFactor::Zero,
+    },
+    alpha: BlendFunction {
+        equation: BlendEquation::Add,
+        source: BlendFactor::DstAlpha,
+        destination: BlendFactor::Zero,
+    },
+};
+
 /// Specifies the polygon rasterization method.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum PolygonMode {
@@ -43,6 +105,11 @@ impl PolygonMode {
 /// Specifies depth buffer testing.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum DepthTest {
+    /// Disable depth testing.
+    ///
+    /// The depth buffer is neither read nor written.
+    Off,
+
     /// Depth test never passes.
     Never,
 
@@ -50,6 +117,26 @@ pub enum DepthTest {
     /// the stored depth value.
     LessThan,
 
+    /// Depth test passes if the incoming depth value is equal to
+    /// the stored depth value.
+    Equal,
+
+    /// Depth test passes if the incoming depth value is less than or
+    /// equal to the stored depth value.
+    LessThanOrEqual,
+
+    /// Depth test passes if the incoming depth value is greater than
+    /// the stored depth value.
+    GreaterThan,
+
+    /// Depth test passes if the incoming depth value is not equal to
+    /// the stored depth value.
+    NotEqual,
+
+    /// Depth test passes if the incoming depth value is greater than or
+    /// equal to the stored depth value.
+    GreaterThanOrEqual,
+
     /// Depth test always passes.
     Always,
 }
@@ -61,15 +148,59 @@ impl Default for DepthTest {
 }
 
 impl DepthTest {
-    pub(crate) fn as_gl_enum(&self) -> u32 {
+    pub(crate) fn as_gl_enum_if_enabled(&self) -> Option<u32> {
         match *self {
-            DepthTest::Never => gl::NEVER,
-            DepthTest::LessThan => gl::LESS,
-            DepthTest::Always => gl::ALWAYS,
+            DepthTest::Off => None,
+            DepthTest::Never => Some(gl::NEVER),
+            DepthTest::LessThan => Some(gl::LESS),
+            DepthTest::Equal => Some(gl::EQUAL),
+            DepthTest::LessThanOrEqual => Some(gl::LEQUAL),
+            DepthTest::GreaterThan => Some(gl::GREATER),
+            DepthTest::NotEqual => Some(gl::NOTEQUAL),
+            DepthTest::GreaterThanOrEqual => Some(gl::GEQUAL),
+            DepthTest::Always => Some(gl::ALWAYS),
         }
     }
 }
 
+/// Maps normalized device depth onto window depth values.
+///
+/// Bounds are compared and hashed by their bit patterns.
+#[derive(Clone, Copy, Debug)]
+pub struct DepthRange {
+    /// Window depth of the near clipping plane, in `0.0 ..= 1.0`.
+    pub near: f64,
+
+    /// Window depth of the far clipping plane, in `0.0 ..= 1.0`.
+    pub far: f64,
+}
+
+impl Default for DepthRange {
+    fn default() -> Self {
+        DepthRange { near: 0.0, far: 1.0 }
+    }
+}
+
+impl DepthRange {
+    fn to_bits(&self) -> [u64; 2] {
+        [self.near.to_bits(), self.far.to_bits()]
+    }
+}
+
+impl cmp::Eq for DepthRange {}
+
+impl cmp::PartialEq<Self> for DepthRange {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
+}
+
+impl hash::Hash for DepthRange {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.to_bits().hash(state);
+    }
+}
+
 /// Specifies the winding order of front facing triangles.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum FrontFace {
@@ -128,6 +259,217 @@ impl Culling {
     }
 }
 
+/// Specifies how the incoming (source) and stored (destination) values
+/// are weighted before blending.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BlendFactor {
+    /// `0`.
+    Zero,
+
+    /// `1`.
+    One,
+
+    /// The source color.
+    SrcColor,
+
+    /// One minus the source color.
+    OneMinusSrcColor,
+
+    /// The destination color.
+    DstColor,
+
+    /// One minus the destination color.
+    OneMinusDstColor,
+
+    /// The source alpha.
+    SrcAlpha,
+
+    /// One minus the source alpha.
+    OneMinusSrcAlpha,
+
+    /// The destination alpha.
+    DstAlpha,
+
+    /// One minus the destination alpha.
+    OneMinusDstAlpha,
+
+    /// The constant blend color.
+    ConstantColor,
+
+    /// One minus the constant blend color.
+    OneMinusConstantColor,
+
+    /// The constant blend alpha.
+    ConstantAlpha,
+
+    /// One minus the constant blend alpha.
+    OneMinusConstantAlpha,
+
+    /// The smaller of the source alpha and one minus the destination
+    /// alpha, or `1` for the alpha channel.
+    SrcAlphaSaturate,
+}
+
+impl BlendFactor {
+    pub(crate) fn as_gl_enum(&self) -> u32 {
+        match *self {
+            BlendFactor::Zero => gl::ZERO,
+            BlendFactor::One => gl::ONE,
+            BlendFactor::SrcColor => gl::SRC_COLOR,
+            BlendFactor::OneMinusSrcColor => gl::ONE_MINUS_SRC_COLOR,
+            BlendFactor::DstColor => gl::DST_COLOR,
+            BlendFactor::OneMinusDstColor => gl::ONE_MINUS_DST_COLOR,
+            BlendFactor::SrcAlpha => gl::SRC_ALPHA,
+            BlendFactor::OneMinusSrcAlpha => gl::ONE_MINUS_SRC_ALPHA,
+            BlendFactor::DstAlpha => gl::DST_ALPHA,
+            BlendFactor::OneMinusDstAlpha => gl::ONE_MINUS_DST_ALPHA,
+            BlendFactor::ConstantColor => gl::CONSTANT_COLOR,
+            BlendFactor::OneMinusConstantColor => gl::ONE_MINUS_CONSTANT_COLOR,
+            BlendFactor::ConstantAlpha => gl::CONSTANT_ALPHA,
+            BlendFactor::OneMinusConstantAlpha => gl::ONE_MINUS_CONSTANT_ALPHA,
+            BlendFactor::SrcAlphaSaturate => gl::SRC_ALPHA_SATURATE,
+        }
+    }
+}
+
+/// Specifies how the weighted source and destination values are combined.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BlendEquation {
+    /// `source * sfactor + destination * dfactor`.
+    Add,
+
+    /// `source * sfactor - destination * dfactor`.
+    Subtract,
+
+    /// `destination * dfactor - source * sfactor`.
+    ReverseSubtract,
+
+    /// `min(source, destination)`, ignoring the blend factors.
+    Min,
+
+    /// `max(source, destination)`, ignoring the blend factors.
+    Max,
+}
+
+impl Default for BlendEquation {
+    fn default() -> Self {
+        BlendEquation::Add
+    }
+}
+
+impl BlendEquation {
+    pub(crate) fn as_gl_enum(&self) -> u32 {
+        match *self {
+            BlendEquation::Add => gl::FUNC_ADD,
+            BlendEquation::Subtract => gl::FUNC_SUBTRACT,
+            BlendEquation::ReverseSubtract => gl::FUNC_REVERSE_SUBTRACT,
+            BlendEquation::Min => gl::MIN,
+            BlendEquation::Max => gl::MAX,
+        }
+    }
+}
+
+/// The blend equation and factors of a set of channels.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlendFunction {
+    /// Combines the weighted source and destination values.
+    pub equation: BlendEquation,
+
+    /// Weights the incoming value.
+    pub source: BlendFactor,
+
+    /// Weights the stored value.
+    pub destination: BlendFactor,
+}
+
+/// Blend state of a color attachment.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Blend {
+    /// Blend function of the red, green, and blue channels.
+    pub rgb: BlendFunction,
+
+    /// Blend function of the alpha channel.
+    pub alpha: BlendFunction,
+}
+
+/// Specifies which color attachments are blended.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Blending {
+    /// Blending is disabled.
+    None,
+
+    /// Every color attachment is blended the same way.
+    All(Blend),
+
+    /// Color attachments are blended independently, indexed by attachment.
+    /// Attachments that are `None` or beyond the end are not blended.
+    ///
+    /// Requires OpenGL 4.0 or `GL_ARB_draw_buffers_blend`.
+    PerAttachment(Vec<Option<Blend>>),
+}
+
+impl Default for Blending {
+    fn default() -> Self {
+        Blending::None
+    }
+}
+
+/// The constant color referred to by the `Constant*` blend factors.
+///
+/// Channels are compared and hashed by their bit patterns.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlendColor {
+    /// Red channel.
+    pub r: f32,
+
+    /// Green channel.
+    pub g: f32,
+
+    /// Blue channel.
+    pub b: f32,
+
+    /// Alpha channel.
+    pub a: f32,
+}
+
+impl BlendColor {
+    fn to_bits(&self) -> [u32; 4] {
+        [self.r.to_bits(), self.g.to_bits(), self.b.to_bits(), self.a.to_bits()]
+    }
+}
+
+impl cmp::Eq for BlendColor {}
+
+impl cmp::PartialEq<Self> for BlendColor {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
+}
+
+impl hash::Hash for BlendColor {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.to_bits().hash(state);
+    }
+}
+
+/// Specifies whether a special index value restarts strip and loop
+/// primitives during indexed draw calls.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PrimitiveRestart {
+    /// Every index is treated as a vertex.
+    Disabled,
+
+    /// Encountering the given index ends the current primitive and begins
+    /// a new one, e.g. `0xFFFF` for `u16` indices.
+    Index(u32),
+}
+
+impl Default for PrimitiveRestart {
+    fn default() -> Self {
+        PrimitiveRestart::Disabled
+    }
+}
+
 /// Viewport region to render to.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Viewport {
@@ -156,6 +498,12 @@ impl Default for Viewport {
 /// Fixed-function state parameters.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct State {
+    /// Color attachment blending.
+    pub blending: Blending,
+
+    /// Constant color used by the `Constant*` blend factors.
+    pub blend_color: BlendColor,
+
     /// Front face winding order.
     pub front_face: FrontFace,
 
@@ -165,9 +513,20 @@ pub struct State {
     /// Hardware depth testing mode.
     pub depth_test: DepthTest,
 
+    /// Write depth values of fragments that pass the depth test.
+    ///
+    /// Has no effect when the depth test is `Off`.
+    pub depth_write: bool,
+
+    /// Mapping of normalized device depth onto the depth buffer.
+    pub depth_range: DepthRange,
+
     /// Specifies the polygon rasterization method.
     pub polygon_mode: PolygonMode,
 
+    /// Specifies the primitive restart index.
+    pub primitive_restart: PrimitiveRestart,
+
     /// Specifies region to render to.
     pub viewport: Viewport,
 }
@@ -1,29 +1,41 @@
 //! GLSL programs.
 
 use queue;
-use std::{cmp, fmt, hash, ops, sync};
+use std::{cmp, fmt, hash, iter, ops, sync};
 
-use buffer::Buffer;
+use buffer;
 use sampler::Sampler2;
-use texture::Texture2;
-
-/// Specifies the maximum number of uniforms permitted by the crate.
-pub const MAX_UNIFORM_BLOCKS: usize = 4;
-
-/// Specifies the maximum number of samplers permitted by the crate.
-pub const MAX_SAMPLERS: usize = 4;
+use texture::{Texture2, TextureBuffer};
 
 /// Program interface binding points.
 ///
-/// The binding indices are the array indices items
-/// are assigned to.
+/// The binding indices are the slice indices items
+/// are assigned to, and must be fewer than the driver limits reported
+/// by [`Factory::max_uniform_blocks`] and [`Factory::max_samplers`].
+///
+/// [`Factory::max_uniform_blocks`]: ../struct.Factory.html#method.max_uniform_blocks
+/// [`Factory::max_samplers`]: ../struct.Factory.html#method.max_samplers
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Bindings {
     /// The program uniform block bindings.
-    pub uniform_blocks: [UniformBlockBinding; MAX_UNIFORM_BLOCKS],
+    pub uniform_blocks: &'static [UniformBlockBinding],
 
     /// The program sampler bindings.
-    pub samplers: [SamplerBinding; MAX_SAMPLERS],
+    pub samplers: &'static [SamplerBinding],
+
+    /// Vertex shader input locations, assigned by name before linking.
+    ///
+    /// Leave empty for shaders that declare `layout(location = N)`.
+    pub attributes: &'static [AttributeBinding],
+
+    /// Fragment shader output color numbers, assigned by name before
+    /// linking.
+    ///
+    /// Leave empty for shaders that declare `layout(location = N)`.
+    pub fragment_outputs: &'static [FragmentOutputBinding],
+
+    /// Vertex shader outputs to capture with transform feedback.
+    pub transform_feedback_varyings: TransformFeedbackVaryings,
 }
 
 /// A binding point for a uniform block in a compiled and linked program.
@@ -64,6 +76,105 @@ impl Default for SamplerBinding {
     }
 }
 
+/// A vertex shader input bound to an attribute location by name.
+///
+/// Unlike uniform blocks and samplers, names are bound before linking, so
+/// names without a matching shader input are silently ignored.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AttributeBinding {
+    /// The named input reads from this attribute location.
+    Named(&'static [u8]),
+
+    /// Binding point is unassigned.
+    None,
+}
+
+impl Default for AttributeBinding {
+    fn default() -> Self {
+        AttributeBinding::None
+    }
+}
+
+/// A fragment shader output bound to a framebuffer color attachment by
+/// name.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FragmentOutputBinding {
+    /// The named output writes to this color attachment.
+    Named(&'static [u8]),
+
+    /// Binding point is unassigned.
+    None,
+}
+
+impl Default for FragmentOutputBinding {
+    fn default() -> Self {
+        FragmentOutputBinding::None
+    }
+}
+
+/// Vertex shader outputs captured by transform feedback.
+///
+/// Names must be NUL-terminated, like other binding names.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TransformFeedbackVaryings {
+    /// All captured outputs are written to a single buffer.
+    Interleaved(&'static [&'static [u8]]),
+
+    /// Each captured output is written to its own buffer binding.
+    Separate(&'static [&'static [u8]]),
+
+    /// No outputs are captured.
+    None,
+}
+
+impl Default for TransformFeedbackVaryings {
+    fn default() -> Self {
+        TransformFeedbackVaryings::None
+    }
+}
+
+/// A vertex shader input reported by program introspection.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ActiveAttribute {
+    /// The GLSL name of the input.
+    pub name: String,
+
+    /// The first attribute location occupied by the input.
+    pub location: u32,
+
+    /// The number of vectors in the input, e.g. 4 for a `mat4` or 2 for a
+    /// `vec3[2]`, each read from its own attribute.
+    pub columns: u32,
+
+    /// The number of components in each vector.
+    pub components: usize,
+
+    /// How the shader reads the input.
+    pub class: buffer::format::Class,
+}
+
+impl ActiveAttribute {
+    /// Returns the attribute locations of each vector in the input.
+    ///
+    /// `dvec3` and `dvec4` vectors occupy two locations each, of which
+    /// only the first is returned.
+    pub fn locations(&self) -> iter::StepBy<ops::Range<u32>> {
+        let double_wide = self.class == buffer::format::Class::Double && self.components > 2;
+        let stride = if double_wide { 2 } else { 1 };
+        (self.location .. self.location + self.columns * stride).step_by(stride as usize)
+    }
+}
+
+/// Output buffers for a transform feedback draw call.
+#[derive(Clone, Copy, Debug)]
+pub struct TransformFeedback<'a> {
+    /// Buffer ranges to capture into, indexed by binding point.
+    pub outputs: &'a [buffer::Slice<'a>],
+
+    /// Discard primitives before rasterization, i.e. only capture.
+    pub rasterizer_discard: bool,
+}
+
 /// Specifies whether the destroyed item was an object or a program.
 #[derive(Clone)]
 pub(crate) enum Destroyed {
@@ -88,17 +199,44 @@ impl ops::Drop for ProgramDestructor {
     }
 }
 
+/// A texture bound to a sampler binding point at draw time.
+#[derive(Clone, Copy, Debug)]
+pub enum SamplerSource<'a> {
+    /// A 2D texture plus sampling properties, read with a `sampler2D`.
+    Texture2(&'a Texture2, Sampler2),
+
+    /// A buffer texture, read with a `samplerBuffer`.
+    Buffer(&'a TextureBuffer),
+}
+
+impl<'a> From<(&'a Texture2, Sampler2)> for SamplerSource<'a> {
+    fn from((texture, sampler): (&'a Texture2, Sampler2)) -> Self {
+        SamplerSource::Texture2(texture, sampler)
+    }
+}
+
+impl<'a> From<&'a TextureBuffer> for SamplerSource<'a> {
+    fn from(texture: &'a TextureBuffer) -> Self {
+        SamplerSource::Buffer(texture)
+    }
+}
+
 /// An invocation of a program whose bindings are known ahead of time.
 #[derive(Clone)]
 pub struct Invocation<'a> {
     /// The program to bind at draw time.
     pub program: &'a Program,
 
-    /// Uniform buffers to be bound to the program at draw time.
-    pub uniforms: [Option<&'a Buffer>; MAX_UNIFORM_BLOCKS],
+    /// Uniform buffer ranges to be bound to the program at draw time.
+    ///
+    /// Slice offsets must be multiples of
+    /// [`Factory::uniform_buffer_offset_alignment`].
+    ///
+    /// [`Factory::uniform_buffer_offset_alignment`]: ../struct.Factory.html#method.uniform_buffer_offset_alignment
+    pub uniforms: &'a [Option<buffer::Slice<'a>>],
 
     /// Texture samplers to be bound to the program at draw time.
-    pub samplers: [Option<(&'a Texture2, Sampler2)>; MAX_SAMPLERS],
+    pub samplers: &'a [Option<SamplerSource<'a>>],
 }
 
 /// A compiled shader program.
@@ -107,8 +245,18 @@ pub struct Program {
     /// The OpenGL program ID.
     id: u32,
 
-    /// Locations of samplers.
-    pub(crate) samplers: [Option<u32>; MAX_SAMPLERS],
+    /// Locations of samplers, indexed by binding.
+    pub(crate) samplers: Vec<Option<u32>>,
+
+    /// Active vertex shader inputs.
+    pub(crate) attributes: Vec<ActiveAttribute>,
+
+    /// The transform feedback primitive mode matching the geometry shader
+    /// output, or `None` if the program has no geometry shader.
+    pub(crate) geometry_output: Option<u32>,
+
+    /// Vertex shader outputs captured by transform feedback, as linked.
+    pub(crate) transform_feedback_varyings: TransformFeedbackVaryings,
 
     /// Returns the program back to the factory upon destruction.
     _destructor: sync::Arc<ProgramDestructor>,
@@ -122,7 +270,10 @@ impl Program {
     ) -> Self {
         Self {
             id,
-            samplers: [None; MAX_SAMPLERS],
+            samplers: Vec::new(),
+            attributes: Vec::new(),
+            geometry_output: None,
+            transform_feedback_varyings: TransformFeedbackVaryings::None,
             _destructor: sync::Arc::new(
                 ProgramDestructor {
                     id,
@@ -136,6 +287,17 @@ impl Program {
     pub(crate) fn id(&self) -> u32 {
         self.id
     }
+
+    /// Returns the active vertex shader inputs, excluding built-ins such
+    /// as `gl_VertexID`.
+    pub fn attributes(&self) -> &[ActiveAttribute] {
+        &self.attributes
+    }
+
+    /// Returns `true` if the program has a geometry shader stage.
+    pub fn has_geometry_shader(&self) -> bool {
+        self.geometry_output.is_some()
+    }
 }
 
 impl cmp::Eq for Program {}
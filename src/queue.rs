@@ -1,7 +1,5 @@
 use crossbeam_channel as chan;
 
-const MAX_QUEUE_SIZE: usize = 1024;
-
 /// Send half of a [`Queue`].
 ///
 /// [`Queue`]: struct.Queue.html
@@ -13,6 +11,9 @@ pub type Sender<T> = chan::Sender<T>;
 pub type Receiver<T> = chan::Receiver<T>;
 
 /// A thread-safe queue.
+///
+/// The queue is unbounded so that dropping GL objects never blocks, even
+/// if the factory has not yet collected previously destroyed objects.
 #[derive(Clone)]
 pub struct Queue<T> {
     /// Send half of the queue.
@@ -25,7 +26,7 @@ pub struct Queue<T> {
 impl<T> Queue<T> {
     /// Constructor.
     pub fn new() -> Self {
-        let (tx, rx) = chan::bounded(MAX_QUEUE_SIZE);
+        let (tx, rx) = chan::unbounded();
         Self { tx, rx }
     }
 
@@ -35,7 +36,6 @@ impl<T> Queue<T> {
     }
 
     /// Remove the item from the front of the queue.
-    #[allow(dead_code)]
     pub fn next(&self) -> Option<T> {
         self.rx.try_recv().ok()
     }
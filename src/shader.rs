@@ -15,6 +15,9 @@ pub enum Kind {
     /// Corresponds to `GL_VERTEX_SHADER`.
     Vertex,
 
+    /// Corresponds to `GL_GEOMETRY_SHADER`.
+    Geometry,
+
     /// Corresponds to `GL_FRAGMENT_SHADER`.
     Fragment,
 }
@@ -24,6 +27,7 @@ impl Kind {
     pub(crate) fn as_gl_enum(self) -> u32 {
         match self {
             Kind::Vertex => gl::VERTEX_SHADER,
+            Kind::Geometry => gl::GEOMETRY_SHADER,
             Kind::Fragment => gl::FRAGMENT_SHADER,
         }
     }
@@ -80,6 +84,11 @@ impl Object {
     pub(crate) fn id(&self) -> u32 {
         self.id
     }
+
+    /// Returns the shader type.
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
 }
 
 impl cmp::Eq for Object {}
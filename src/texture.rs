@@ -1,5 +1,7 @@
 //! GPU-visible pixel container.
 
+use buffer::Buffer;
+use fence::Fence;
 use gl;
 use queue;
 use std::{cmp, fmt, hash, ops, sync};
@@ -68,6 +70,114 @@ impl From<format::U8> for Format {
     }
 }
 
+/// Format of texels fetched from a [`TextureBuffer`].
+///
+/// [`TextureBuffer`]: struct.TextureBuffer.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TexelFormat {
+    /// Corresponds to `GL_R8`.
+    R8,
+    /// Corresponds to `GL_R16`.
+    R16,
+    /// Corresponds to `GL_R16F`.
+    R16F,
+    /// Corresponds to `GL_R32F`.
+    R32F,
+    /// Corresponds to `GL_R8I`.
+    R8I,
+    /// Corresponds to `GL_R16I`.
+    R16I,
+    /// Corresponds to `GL_R32I`.
+    R32I,
+    /// Corresponds to `GL_R8UI`.
+    R8UI,
+    /// Corresponds to `GL_R16UI`.
+    R16UI,
+    /// Corresponds to `GL_R32UI`.
+    R32UI,
+
+    /// Corresponds to `GL_RG8`.
+    Rg8,
+    /// Corresponds to `GL_RG16`.
+    Rg16,
+    /// Corresponds to `GL_RG16F`.
+    Rg16F,
+    /// Corresponds to `GL_RG32F`.
+    Rg32F,
+    /// Corresponds to `GL_RG8I`.
+    Rg8I,
+    /// Corresponds to `GL_RG16I`.
+    Rg16I,
+    /// Corresponds to `GL_RG32I`.
+    Rg32I,
+    /// Corresponds to `GL_RG8UI`.
+    Rg8UI,
+    /// Corresponds to `GL_RG16UI`.
+    Rg16UI,
+    /// Corresponds to `GL_RG32UI`.
+    Rg32UI,
+
+    /// Corresponds to `GL_RGBA8`.
+    Rgba8,
+    /// Corresponds to `GL_RGBA16`.
+    Rgba16,
+    /// Corresponds to `GL_RGBA16F`.
+    Rgba16F,
+    /// Corresponds to `GL_RGBA32F`.
+    Rgba32F,
+    /// Corresponds to `GL_RGBA8I`.
+    Rgba8I,
+    /// Corresponds to `GL_RGBA16I`.
+    Rgba16I,
+    /// Corresponds to `GL_RGBA32I`.
+    Rgba32I,
+    /// Corresponds to `GL_RGBA8UI`.
+    Rgba8UI,
+    /// Corresponds to `GL_RGBA16UI`.
+    Rgba16UI,
+    /// Corresponds to `GL_RGBA32UI`.
+    Rgba32UI,
+}
+
+impl TexelFormat {
+    pub(crate) fn as_gl_enum(&self) -> u32 {
+        match *self {
+            TexelFormat::R8 => gl::R8,
+            TexelFormat::R16 => gl::R16,
+            TexelFormat::R16F => gl::R16F,
+            TexelFormat::R32F => gl::R32F,
+            TexelFormat::R8I => gl::R8I,
+            TexelFormat::R16I => gl::R16I,
+            TexelFormat::R32I => gl::R32I,
+            TexelFormat::R8UI => gl::R8UI,
+            TexelFormat::R16UI => gl::R16UI,
+            TexelFormat::R32UI => gl::R32UI,
+
+            TexelFormat::Rg8 => gl::RG8,
+            TexelFormat::Rg16 => gl::RG16,
+            TexelFormat::Rg16F => gl::RG16F,
+            TexelFormat::Rg32F => gl::RG32F,
+            TexelFormat::Rg8I => gl::RG8I,
+            TexelFormat::Rg16I => gl::RG16I,
+            TexelFormat::Rg32I => gl::RG32I,
+            TexelFormat::Rg8UI => gl::RG8UI,
+            TexelFormat::Rg16UI => gl::RG16UI,
+            TexelFormat::Rg32UI => gl::RG32UI,
+
+            TexelFormat::Rgba8 => gl::RGBA8,
+            TexelFormat::Rgba16 => gl::RGBA16,
+            TexelFormat::Rgba16F => gl::RGBA16F,
+            TexelFormat::Rgba32F => gl::RGBA32F,
+            TexelFormat::Rgba8I => gl::RGBA8I,
+            TexelFormat::Rgba16I => gl::RGBA16I,
+            TexelFormat::Rgba32I => gl::RGBA32I,
+            TexelFormat::Rgba8UI => gl::RGBA8UI,
+            TexelFormat::Rgba16UI => gl::RGBA16UI,
+            TexelFormat::Rgba32UI => gl::RGBA32UI,
+        }
+    }
+}
+
 /// Returns the texture back to the factory upon destruction.
 pub(crate) struct Destructor {
     id: Id,
@@ -165,3 +275,124 @@ impl hash::Hash for Texture2 {
     }
 }
 
+/// A texture whose texels are sourced from a [`Buffer`], sampled in GLSL
+/// with a `samplerBuffer`.
+///
+/// [`Buffer`]: ../buffer/struct.Buffer.html
+#[derive(Clone)]
+pub struct TextureBuffer {
+    /// The OpenGL texture ID.
+    id: Id,
+
+    /// The buffer providing texel data.
+    buffer: Buffer,
+
+    /// The format of texels in the buffer.
+    format: TexelFormat,
+
+    /// Returns the texture back to the factory upon destruction.
+    _destructor: sync::Arc<Destructor>,
+}
+
+impl TextureBuffer {
+    pub(crate) fn new(
+        id: Id,
+        buffer: Buffer,
+        format: TexelFormat,
+        tx: queue::Sender<Id>,
+    ) -> Self {
+        TextureBuffer {
+            id,
+            buffer,
+            format,
+            _destructor: sync::Arc::new(Destructor { id, tx }),
+        }
+    }
+
+    /// Returns the OpenGL texture ID.
+    pub(crate) fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Returns the buffer providing texel data.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Returns the format of texels in the buffer.
+    pub fn format(&self) -> TexelFormat {
+        self.format
+    }
+}
+
+impl cmp::Eq for TextureBuffer {}
+
+impl cmp::PartialEq<Self> for TextureBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl fmt::Debug for TextureBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[derive(Debug)]
+        struct TextureBuffer<'a>(u32, &'a Buffer, TexelFormat);
+
+        TextureBuffer(self.id, &self.buffer, self.format).fmt(f)
+    }
+}
+
+impl hash::Hash for TextureBuffer {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// A pending read back of texture contents into GPU memory.
+///
+/// Poll with [`Factory::poll_readback`] and retrieve the data with
+/// [`Factory::finish_readback`].
+///
+/// [`Factory::poll_readback`]: ../struct.Factory.html#method.poll_readback
+/// [`Factory::finish_readback`]: ../struct.Factory.html#method.finish_readback
+#[derive(Debug)]
+pub struct Readback {
+    /// The pixel pack buffer receiving the texture contents.
+    buffer: Buffer,
+
+    /// The number of bytes written into the buffer.
+    size: usize,
+
+    /// Signalled once the texture contents have been written.
+    fence: Fence,
+}
+
+impl Readback {
+    /// Constructor.
+    pub(crate) fn new(buffer: Buffer, size: usize, fence: Fence) -> Self {
+        Self { buffer, size, fence }
+    }
+
+    /// Returns the pixel pack buffer receiving the texture contents.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Returns the number of bytes written into the buffer.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the fence signalled once the texture contents are written.
+    pub fn fence(&self) -> &Fence {
+        &self.fence
+    }
+
+    /// Returns the pixel pack buffer, for reuse with
+    /// [`Factory::readback_texture2_into`].
+    ///
+    /// [`Factory::readback_texture2_into`]: ../struct.Factory.html#method.readback_texture2_into
+    pub fn into_buffer(self) -> Buffer {
+        self.buffer
+    }
+}
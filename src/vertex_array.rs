@@ -4,18 +4,74 @@ use buffer;
 use queue;
 use std::{cmp, fmt, hash, ops, sync};
 
-/// The maximum number of vertex attributes permitted by the crate.
-pub const MAX_ATTRIBUTES: usize = 8;
-
 /// The OpenGL VAO ID type.
 pub(crate) type Id = u32;
 
+/// A constant vertex attribute value, shared by every vertex.
+///
+/// Values are always four components wide; shader inputs with fewer
+/// components read a prefix.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Constant {
+    /// Read as `float`, `vecN`, etc. Corresponds to `glVertexAttrib4f`.
+    F32([f32; 4]),
+
+    /// Read as `int`, `ivecN`, etc. Corresponds to `glVertexAttribI4i`.
+    I32([i32; 4]),
+
+    /// Read as `uint`, `uvecN`, etc. Corresponds to `glVertexAttribI4ui`.
+    U32([u32; 4]),
+}
+
 /// Vertex attribute.
-pub type Attribute = buffer::Accessor;
+#[derive(Clone, Debug)]
+pub enum Attribute {
+    /// Per-vertex (or per-instance) data read from a buffer.
+    Buffer(buffer::Accessor),
+
+    /// The same value for every vertex, applied at draw time.
+    Constant(Constant),
+}
+
+impl Attribute {
+    /// Returns the instance divisor of the attribute, which is zero for
+    /// constant attributes.
+    pub fn divisor(&self) -> u32 {
+        match *self {
+            Attribute::Buffer(ref accessor) => accessor.divisor(),
+            Attribute::Constant(_) => 0,
+        }
+    }
+}
+
+impl From<buffer::Accessor> for Attribute {
+    fn from(accessor: buffer::Accessor) -> Self {
+        Attribute::Buffer(accessor)
+    }
+}
+
+impl From<Constant> for Attribute {
+    fn from(constant: Constant) -> Self {
+        Attribute::Constant(constant)
+    }
+}
 
 /// Index data.
 pub type Indices = buffer::Accessor;
 
+/// A vertex type whose attribute layout is known ahead of time.
+///
+/// With the `derive` feature enabled this may be implemented for
+/// `#[repr(C)]` structs with `#[derive(Vertex)]`.
+pub trait Vertex {
+    /// Returns the attributes reading `Self` elements from `buffer`,
+    /// indexed by attribute location.
+    fn attributes(buffer: &buffer::Buffer) -> Vec<Option<Attribute>>;
+}
+
+#[cfg(feature = "derive")]
+pub use gpu_derive::Vertex;
+
 /// Returns the VAO back to the factory upon destruction.
 struct Destructor {
     id: u32,
@@ -28,17 +84,30 @@ impl ops::Drop for Destructor {
     }
 }
 
+/// The bindings of a vertex array as last specified through the factory.
+#[derive(Debug)]
+struct Cache {
+    /// Draw sequence indices to bind at draw time.
+    indices: Option<Indices>,
+
+    /// Vertex attributes to bind at draw time, indexed by location.
+    attributes: Vec<Option<Attribute>>,
+}
+
 /// Corresponds to an OpenGL vertex array object.
+///
+/// Clones share the same VAO and the same cached bindings, so changes made
+/// through [`Factory::set_vertex_attribute`] and friends are visible from
+/// every clone.
+///
+/// [`Factory::set_vertex_attribute`]: ../struct.Factory.html#method.set_vertex_attribute
 #[derive(Clone)]
 pub struct VertexArray {
     /// The OpenGL VAO ID.
     id: Id,
-    
-    /// Draw sequence indices to bind at draw time.
-    indices: Option<Indices>,
 
-    /// Vertex attributes to bind at draw time.
-    attributes: [Option<Attribute>; MAX_ATTRIBUTES],
+    /// Bindings shared between clones.
+    cache: sync::Arc<sync::Mutex<Cache>>,
 
     /// Returns the VAO back to the factory upon destruction.
     destructor: sync::Arc<Destructor>,
@@ -48,14 +117,13 @@ impl VertexArray {
     /// Constructor.
     pub(crate) fn new(
         id: Id,
-        attributes: [Option<Attribute>; MAX_ATTRIBUTES],
+        attributes: Vec<Option<Attribute>>,
         indices: Option<Indices>,
         tx: queue::Sender<Id>,
     ) -> Self {
         Self {
             id,
-            indices,
-            attributes,
+            cache: sync::Arc::new(sync::Mutex::new(Cache { indices, attributes })),
             destructor: sync::Arc::new(Destructor { id, tx }),
         }
     }
@@ -65,14 +133,55 @@ impl VertexArray {
         self.id
     }
 
+    /// Locks the shared bindings.
+    ///
+    /// Validation panics while the lock is held leave the bindings intact,
+    /// so a poisoned lock is recovered rather than propagated.
+    fn cache(&self) -> sync::MutexGuard<Cache> {
+        self.cache.lock().unwrap_or_else(sync::PoisonError::into_inner)
+    }
+
     /// Returns the accessor bound as the element array buffer.
-    pub fn indices(&self) -> Option<&Indices> {
-        self.indices.as_ref()
+    pub fn indices(&self) -> Option<Indices> {
+        self.cache().indices.clone()
     }
 
     /// Returns the accessor bound to the given attribute index.
-    pub fn attribute(&self, index: u8) -> Option<&Attribute> {
-        self.attributes[index as usize].as_ref()
+    pub fn attribute(&self, index: u8) -> Option<Attribute> {
+        self.cache().attributes.get(index as usize).cloned().and_then(|x| x)
+    }
+
+    /// Returns the accessors bound to each attribute index.
+    pub fn attributes(&self) -> Vec<Option<Attribute>> {
+        self.cache().attributes.clone()
+    }
+
+    /// Calls `func` with the accessors bound to each attribute index,
+    /// without copying them.
+    pub fn with_attributes<F, R>(&self, func: F) -> R
+        where F: FnOnce(&[Option<Attribute>]) -> R
+    {
+        func(&self.cache().attributes)
+    }
+
+    /// Replaces the cached accessor bound to the given attribute index,
+    /// returning the previous one.
+    pub(crate) fn replace_attribute(
+        &self,
+        index: u8,
+        attribute: Option<Attribute>,
+    ) -> Option<Attribute> {
+        let index = index as usize;
+        let mut cache = self.cache();
+        if index >= cache.attributes.len() {
+            cache.attributes.resize(index + 1, None);
+        }
+        ::std::mem::replace(&mut cache.attributes[index], attribute)
+    }
+
+    /// Replaces the cached index accessor.
+    pub(crate) fn set_indices(&self, indices: Option<Indices>) {
+        self.cache().indices = indices;
     }
 }
 
@@ -93,10 +202,11 @@ impl fmt::Debug for VertexArray {
             attributes: &'a [Option<Attribute>],
         }
 
+        let cache = self.cache();
         VertexArray {
             id: self.id,
-            indices: self.indices.as_ref(),
-            attributes: &self.attributes,
+            indices: cache.indices.as_ref(),
+            attributes: &cache.attributes,
         }.fmt(f)
     }
 }